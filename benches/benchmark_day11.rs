@@ -0,0 +1,17 @@
+use aoc_2024::days::day11::{read_input, update_line_hashmap, StoneCounter};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn benchmark_day11(c: &mut Criterion) {
+    let line = read_input("data/day11.txt").expect("Failed to read input");
+
+    c.bench_function("update_line_hashmap_75", |b| {
+        b.iter(|| update_line_hashmap(black_box(&line), 75))
+    });
+
+    c.bench_function("stone_counter_75", |b| {
+        b.iter(|| StoneCounter::new().count_line(black_box(&line), 75))
+    });
+}
+
+criterion_group!(benches, benchmark_day11);
+criterion_main!(benches);
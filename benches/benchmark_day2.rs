@@ -1,4 +1,6 @@
-use aoc_2024::days::day02::{count_safe_reports, count_safe_reports_dampener, read_input};
+use aoc_2024::days::day02::{
+    count_safe_reports, count_safe_reports_dampener, count_safe_reports_dampener_naive, read_input,
+};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn benchmark_day02(c: &mut Criterion) {
@@ -11,6 +13,10 @@ fn benchmark_day02(c: &mut Criterion) {
     c.bench_function("count_safe_reports_dampener", |b| {
         b.iter(|| count_safe_reports_dampener(black_box(&reports)))
     });
+
+    c.bench_function("count_safe_reports_dampener_naive", |b| {
+        b.iter(|| count_safe_reports_dampener_naive(black_box(&reports)))
+    });
 }
 
 criterion_group!(benches, benchmark_day02);
@@ -1,9 +1,14 @@
-use aoc_2024::days::day03::{compute_enabled_multiplications, compute_multiplications, read_input};
+use aoc_2024::days::day03::{
+    compute_enabled_multiplications, compute_multiplications, read_input,
+    sum_enabled_multiplications, sum_multiplications,
+};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::fs;
 
 fn benchmark_day03(c: &mut Criterion) {
     let corrupted_memory =
         read_input("data/day03.txt").expect("Failed to read and parse the input file");
+    let bytes = fs::read("data/day03.txt").expect("Failed to read the input file");
 
     c.bench_function("compute_multiplications", |b| {
         b.iter(|| compute_multiplications(black_box(&corrupted_memory)))
@@ -12,6 +17,14 @@ fn benchmark_day03(c: &mut Criterion) {
     c.bench_function("compute_enabled_multiplications", |b| {
         b.iter(|| compute_enabled_multiplications(black_box(&corrupted_memory)))
     });
+
+    c.bench_function("sum_multiplications_streaming", |b| {
+        b.iter(|| sum_multiplications(black_box(&bytes[..])))
+    });
+
+    c.bench_function("sum_enabled_multiplications_streaming", |b| {
+        b.iter(|| sum_enabled_multiplications(black_box(&bytes[..])))
+    });
 }
 
 criterion_group!(benches, benchmark_day03);
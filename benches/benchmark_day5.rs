@@ -1,16 +1,32 @@
-use aoc_2024::days::day05::{middle_page_sum, read_input, reordered_middle_page_sum};
+use aoc_2024::days::day05::{
+    middle_page_sum, middle_page_sum_matrix, read_input, reordered_middle_page_sum,
+    reordered_middle_page_sum_matrix, RuleMatrix,
+};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn benchmark_day05(c: &mut Criterion) {
     let (rules, updates) = read_input("data/day05.txt").expect("Failed to read input");
+    let matrix = RuleMatrix::from_rules(&rules).expect("Failed to build rule matrix");
 
     c.bench_function("middle_page_sum", |b| {
         b.iter(|| middle_page_sum(black_box(&updates), black_box(&rules)))
     });
 
+    c.bench_function("middle_page_sum_matrix", |b| {
+        b.iter(|| middle_page_sum_matrix(black_box(&updates), black_box(&matrix)))
+    });
+
     c.bench_function("reordered_middle_page_sum", |b| {
         b.iter(|| reordered_middle_page_sum(black_box(&updates), black_box(&rules)))
     });
+
+    c.bench_function("reordered_middle_page_sum_matrix", |b| {
+        b.iter(|| reordered_middle_page_sum_matrix(black_box(&updates), black_box(&matrix)))
+    });
+
+    c.bench_function("rule_matrix_from_rules", |b| {
+        b.iter(|| RuleMatrix::from_rules(black_box(&rules)))
+    });
 }
 
 criterion_group!(benches, benchmark_day05);
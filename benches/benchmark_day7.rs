@@ -1,15 +1,47 @@
-use aoc_2024::days::day07::{read_input, total_calibration, total_calibration_concat};
+use aoc_2024::days::day07::{
+    basic_operators, concat_operators, read_input, read_input_as, total_calibration,
+    total_calibration_concat_expanded, total_calibration_expanded, total_calibration_forward,
+};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use num_bigint::BigUint;
 
 fn benchmark_day07(c: &mut Criterion) {
     let calibration_data = read_input("data/day07.txt").expect("Failed to read input");
+    let calibration_data_u128 =
+        read_input_as::<u128>("data/day07.txt").expect("Failed to read input");
+    let calibration_data_big =
+        read_input_as::<BigUint>("data/day07.txt").expect("Failed to read input");
+
+    c.bench_function("total_calibration_expanded", |b| {
+        b.iter(|| total_calibration_expanded(black_box(&calibration_data)))
+    });
+
+    c.bench_function("total_calibration_forward", |b| {
+        b.iter(|| total_calibration_forward(black_box(&calibration_data), &basic_operators()))
+    });
 
     c.bench_function("total_calibration", |b| {
-        b.iter(|| total_calibration(black_box(&calibration_data)))
+        b.iter(|| total_calibration(black_box(&calibration_data), &basic_operators()))
+    });
+
+    c.bench_function("total_calibration_concat_expanded", |b| {
+        b.iter(|| total_calibration_concat_expanded(black_box(&calibration_data)))
+    });
+
+    c.bench_function("total_calibration_concat_forward", |b| {
+        b.iter(|| total_calibration_forward(black_box(&calibration_data), &concat_operators()))
     });
 
     c.bench_function("total_calibration_concat", |b| {
-        b.iter(|| total_calibration_concat(black_box(&calibration_data)))
+        b.iter(|| total_calibration(black_box(&calibration_data), &concat_operators()))
+    });
+
+    c.bench_function("total_calibration_concat_u128", |b| {
+        b.iter(|| total_calibration(black_box(&calibration_data_u128), &concat_operators()))
+    });
+
+    c.bench_function("total_calibration_concat_biguint", |b| {
+        b.iter(|| total_calibration(black_box(&calibration_data_big), &concat_operators()))
     });
 }
 
@@ -0,0 +1,37 @@
+use aoc_2024::days::day09::{process_disk, process_disk_whole_files, read_input};
+use aoc_2024::random::Lcg;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn synthetic_disk(len: usize) -> Vec<usize> {
+    let mut rng = Lcg::new(1);
+    (0..len)
+        .map(|idx| {
+            let digit = rng.next_below(10) as usize;
+            if idx % 2 == 0 {
+                digit.max(1)
+            } else {
+                digit
+            }
+        })
+        .collect()
+}
+
+fn benchmark_day09(c: &mut Criterion) {
+    let memory = read_input("data/day09.txt").expect("Failed to read input");
+    let large_memory = synthetic_disk(1_000_001);
+
+    c.bench_function("process_disk", |b| {
+        b.iter(|| process_disk(black_box(&memory)))
+    });
+
+    c.bench_function("process_disk_whole_files", |b| {
+        b.iter(|| process_disk_whole_files(black_box(&memory)))
+    });
+
+    c.bench_function("process_disk_whole_files_large", |b| {
+        b.iter(|| process_disk_whole_files(black_box(&large_memory)))
+    });
+}
+
+criterion_group!(benches, benchmark_day09);
+criterion_main!(benches);
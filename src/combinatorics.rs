@@ -0,0 +1,162 @@
+use rayon::prelude::*;
+
+pub struct Pairs<'a, T> {
+    items: &'a [T],
+    i: usize,
+    j: usize,
+}
+
+pub fn pairs<T>(items: &[T]) -> Pairs<'_, T> {
+    Pairs { items, i: 0, j: 1 }
+}
+
+impl<'a, T> Iterator for Pairs<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.i < self.items.len() {
+            if self.j < self.items.len() {
+                let pair = (&self.items[self.i], &self.items[self.j]);
+                self.j += 1;
+                return Some(pair);
+            }
+            self.i += 1;
+            self.j = self.i + 1;
+        }
+        None
+    }
+}
+
+pub fn par_pairs<T: Sync>(items: &[T]) -> impl ParallelIterator<Item = (&T, &T)> {
+    (0..items.len())
+        .into_par_iter()
+        .flat_map_iter(move |i| items[i + 1..].iter().map(move |other| (&items[i], other)))
+}
+
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (0..k).collect(),
+        done: k > items.len(),
+    }
+}
+
+impl<'a, T> Iterator for Combinations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let combination = self.indices.iter().map(|&idx| &self.items[idx]).collect();
+
+        let (n, k) = (self.items.len(), self.indices.len());
+        match (0..k).rev().find(|&i| self.indices[i] < n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(combination)
+    }
+}
+
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+pub fn permutations<T>(items: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        items,
+        indices: (0..items.len()).collect(),
+        done: false,
+    }
+}
+
+impl<'a, T> Iterator for Permutations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let permutation = self.indices.iter().map(|&idx| &self.items[idx]).collect();
+
+        // Advance the indices to the next lexicographic permutation.
+        match (1..self.indices.len())
+            .rev()
+            .find(|&i| self.indices[i - 1] < self.indices[i])
+        {
+            Some(i) => {
+                let pivot = self.indices[i - 1];
+                let swap = (i..self.indices.len())
+                    .rev()
+                    .find(|&j| self.indices[j] > pivot)
+                    .unwrap();
+                self.indices.swap(i - 1, swap);
+                self.indices[i..].reverse();
+            }
+            None => self.done = true,
+        }
+        Some(permutation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairs() {
+        let items = vec![1, 2, 3, 4];
+        let result: Vec<(i32, i32)> = pairs(&items).map(|(a, b)| (*a, *b)).collect();
+        let expected = vec![(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+        assert_eq!(result, expected, "Test pairs failed");
+
+        let mut result: Vec<(i32, i32)> = par_pairs(&items).map(|(a, b)| (*a, *b)).collect();
+        result.sort_unstable();
+        assert_eq!(result, expected, "Test parallel pairs failed");
+
+        assert_eq!(pairs(&[1]).count(), 0);
+        assert_eq!(pairs::<i32>(&[]).count(), 0);
+    }
+
+    #[test]
+    fn test_combinations() {
+        let items = vec!['a', 'b', 'c', 'd'];
+        let result: Vec<String> = combinations(&items, 3)
+            .map(|combination| combination.into_iter().collect())
+            .collect();
+        let expected = vec!["abc", "abd", "acd", "bcd"];
+        assert_eq!(result, expected, "Test combinations failed");
+
+        assert_eq!(combinations(&items, 0).count(), 1);
+        assert_eq!(combinations(&items, 2).count(), 6);
+        assert_eq!(combinations(&items, 4).count(), 1);
+        assert_eq!(combinations(&items, 5).count(), 0);
+    }
+
+    #[test]
+    fn test_permutations() {
+        let items = vec!['a', 'b', 'c'];
+        let result: Vec<String> = permutations(&items)
+            .map(|permutation| permutation.into_iter().collect())
+            .collect();
+        let expected = vec!["abc", "acb", "bac", "bca", "cab", "cba"];
+        assert_eq!(result, expected, "Test permutations failed");
+
+        assert_eq!(permutations(&[1, 2, 3, 4, 5]).count(), 120);
+        assert_eq!(permutations::<i32>(&[]).count(), 1);
+    }
+}
@@ -91,7 +91,7 @@ mod tests {
         let result_1 = sorted_difference(&first, &second);
         let result_2 = similarity_score(&first, &second);
 
-        assert_eq!(result_1, 2970687, "Day 01 - Part 1 failed"); 
-        assert_eq!(result_2, 23963899, "Day 01 - Part 2 failed"); 
+        assert_eq!(result_1, 2970687, "Day 01 - Part 1 failed");
+        assert_eq!(result_2, 23963899, "Day 01 - Part 2 failed");
     }
 }
@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fmt;
 use std::fs;
 use std::path::Path;
 
@@ -10,6 +12,33 @@ pub fn run() {
     println!("Day 02 - part 2: {}", result_2);
 }
 
+pub fn run_diagnostics() {
+    let reports = read_input("data/day02.txt").expect("Failed to read and parse the input file");
+    let policy = SafetyPolicy::part_1();
+    let verdicts: Vec<ReportVerdict> = reports
+        .iter()
+        .map(|report| policy.verdict(report))
+        .collect();
+
+    let mut by_kind: BTreeMap<FailureKind, (usize, usize)> = BTreeMap::new();
+    for verdict in &verdicts {
+        if let Some(failure) = verdict.failure {
+            let entry = by_kind.entry(failure.kind).or_insert((0, 0));
+            entry.0 += 1;
+            if verdict.rescue.is_some() {
+                entry.1 += 1;
+            }
+        }
+    }
+    let safe = verdicts.iter().filter(|verdict| verdict.is_safe()).count();
+    println!("{:<16} {:>8} {:>8}", "verdict", "reports", "rescued");
+    println!("{:<16} {:>8} {:>8}", "safe", safe, "-");
+    for (kind, (count, rescued)) in &by_kind {
+        println!("{:<16} {:>8} {:>8}", kind.to_string(), count, rescued);
+    }
+    println!("{:<16} {:>8}", "total", verdicts.len());
+}
+
 pub fn read_input(path: &str) -> Result<Vec<Vec<i32>>, Box<dyn Error>> {
     let content = fs::read_to_string(Path::new(path))
         .map_err(|e| format!("Failed to read input file '{}': {}", path, e))?;
@@ -24,41 +53,254 @@ pub fn read_input(path: &str) -> Result<Vec<Vec<i32>>, Box<dyn Error>> {
         .collect())
 }
 
-fn is_difference_safe(difference: &i32) -> bool {
-    if *difference == 0 {
-        return false;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    pub min_step: i32,
+    pub max_step: i32,
+    pub monotonic: bool,
+    pub max_removals: usize,
+}
+
+impl SafetyPolicy {
+    pub const fn part_1() -> Self {
+        SafetyPolicy {
+            min_step: 1,
+            max_step: 3,
+            monotonic: true,
+            max_removals: 0,
+        }
+    }
+
+    pub const fn part_2() -> Self {
+        SafetyPolicy {
+            max_removals: 1,
+            ..SafetyPolicy::part_1()
+        }
+    }
+
+    // `Some(true)` for increasing, `Some(false)` for decreasing, `None` when steps may go either way.
+    fn directions(&self) -> &'static [Option<bool>] {
+        if self.monotonic {
+            &[Some(true), Some(false)]
+        } else {
+            &[None]
+        }
     }
-    const SAFE_THRESHOLD: i32 = 4;
-    if difference.abs() < SAFE_THRESHOLD {
-        return true;
+
+    fn is_step_safe(&self, from: i32, to: i32, direction: Option<bool>) -> bool {
+        let step = match direction {
+            Some(true) => to - from,
+            Some(false) => from - to,
+            None => (to - from).abs(),
+        };
+        (self.min_step..=self.max_step).contains(&step)
+    }
+
+    // `removals[i]` is the fewest levels dropped before `i` in a safe run of at least two
+    // levels ending at `i`. Runs only need predecessors at most `max_removals + 1` back, so
+    // this is O(n * k) instead of enumerating subsets.
+    fn min_removals_in_direction(&self, report: &[i32], direction: Option<bool>) -> Option<usize> {
+        let n = report.len();
+        let mut removals: Vec<Option<usize>> = vec![None; n];
+        let mut best: Option<usize> = None;
+        for i in 0..n {
+            for j in i.saturating_sub(self.max_removals + 1)..i {
+                if !self.is_step_safe(report[j], report[i], direction) {
+                    continue;
+                }
+                let candidate = removals[j].map_or(j, |before| before.min(j)) + (i - j - 1);
+                removals[i] = Some(removals[i].map_or(candidate, |r| r.min(candidate)));
+            }
+            if let Some(before) = removals[i] {
+                let total = before + (n - 1 - i);
+                best = Some(best.map_or(total, |b| b.min(total)));
+            }
+        }
+        best.filter(|&total| total <= self.max_removals)
+    }
+
+    fn is_safe_in_direction(&self, report: &[i32], direction: Option<bool>) -> bool {
+        report.len() >= 2
+            && report
+                .windows(2)
+                .all(|pair| self.is_step_safe(pair[0], pair[1], direction))
+    }
+
+    fn is_safe_without(&self, report: &[i32], skip: usize, direction: Option<bool>) -> bool {
+        let mut levels = report
+            .iter()
+            .enumerate()
+            .filter(|&(idx, _)| idx != skip)
+            .map(|(_, level)| *level);
+        let mut previous = match levels.next() {
+            Some(level) => level,
+            None => return false,
+        };
+        let mut len = 1;
+        for level in levels {
+            if !self.is_step_safe(previous, level, direction) {
+                return false;
+            }
+            previous = level;
+            len += 1;
+        }
+        len >= 2
+    }
+
+    // Any single removal that fixes the report has to drop one end of its first bad step.
+    fn find_removal_in_direction(&self, report: &[i32], direction: Option<bool>) -> Option<usize> {
+        match (0..report.len() - 1)
+            .find(|&idx| !self.is_step_safe(report[idx], report[idx + 1], direction))
+        {
+            None => Some(0),
+            Some(idx) => [idx, idx + 1]
+                .into_iter()
+                .find(|&skip| self.is_safe_without(report, skip, direction)),
+        }
+    }
+
+    pub fn find_removal(&self, report: &[i32]) -> Option<usize> {
+        if report.len() < 3 {
+            return None;
+        }
+        self.directions()
+            .iter()
+            .find_map(|&direction| self.find_removal_in_direction(report, direction))
+    }
+
+    // Fewest levels to remove for the report to be safe, or None if more than `max_removals`.
+    pub fn min_removals(&self, report: &[i32]) -> Option<usize> {
+        let directions = self.directions();
+        if directions
+            .iter()
+            .any(|&direction| self.is_safe_in_direction(report, direction))
+        {
+            return Some(0);
+        }
+        match self.max_removals {
+            0 => None,
+            1 => self.find_removal(report).map(|_| 1),
+            _ => directions
+                .iter()
+                .filter_map(|&direction| self.min_removals_in_direction(report, direction))
+                .min(),
+        }
+    }
+
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        self.min_removals(report).is_some()
+    }
+
+    pub fn count_safe(&self, reports: &[Vec<i32>]) -> i32 {
+        reports
+            .iter()
+            .filter(|report| self.is_safe(report))
+            .count()
+            .try_into()
+            .unwrap()
     }
-    false
 }
 
-fn is_report_safe(report: &[i32]) -> bool {
-    if report.len() < 2 {
-        return false;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureKind {
+    TooShort,
+    ZeroStep,
+    StepTooLarge,
+    DirectionChange,
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FailureKind::TooShort => "too short",
+            FailureKind::ZeroStep => "zero step",
+            FailureKind::StepTooLarge => "step too large",
+            FailureKind::DirectionChange => "direction change",
+        };
+        write!(f, "{}", name)
     }
+}
 
-    let mut diffs = report.windows(2).map(|pair| pair[1] - pair[0]);
-    let first_diff = match diffs.next() {
-        Some(d) if is_difference_safe(&d) => d,
-        _ => return false,
-    };
-    let is_increasing = first_diff > 0;
-    diffs.all(|diff| is_difference_safe(&diff) && ((diff > 0) == is_increasing))
+// `index` is the level where the offending step starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Failure {
+    pub kind: FailureKind,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportVerdict {
+    pub failure: Option<Failure>,
+    pub rescue: Option<usize>,
+}
+
+impl ReportVerdict {
+    pub fn is_safe(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    pub fn is_rescued(&self) -> bool {
+        self.failure.is_some() && self.rescue.is_some()
+    }
+}
+
+impl SafetyPolicy {
+    // Steps shorter than `min_step` count as zero steps. A monotonic report takes its direction
+    // from its first non-flat step.
+    fn first_failure(&self, report: &[i32]) -> Option<Failure> {
+        if report.len() < 2 {
+            return Some(Failure {
+                kind: FailureKind::TooShort,
+                index: 0,
+            });
+        }
+        let mut direction = None;
+        report.windows(2).enumerate().find_map(|(index, pair)| {
+            let step = pair[1] - pair[0];
+            let kind = if step.abs() < self.min_step {
+                Some(FailureKind::ZeroStep)
+            } else if self.monotonic
+                && step != 0
+                && *direction.get_or_insert(step > 0) != (step > 0)
+            {
+                Some(FailureKind::DirectionChange)
+            } else if step.abs() > self.max_step {
+                Some(FailureKind::StepTooLarge)
+            } else {
+                None
+            };
+            kind.map(|kind| Failure { kind, index })
+        })
+    }
+
+    pub fn verdict(&self, report: &[i32]) -> ReportVerdict {
+        let failure = self.first_failure(report);
+        let rescue = failure.and_then(|_| self.find_removal(report));
+        ReportVerdict { failure, rescue }
+    }
+}
+
+fn is_report_safe(report: &[i32]) -> bool {
+    SafetyPolicy::part_1().is_safe(report)
 }
 
 pub fn count_safe_reports(reports: &[Vec<i32>]) -> i32 {
-    reports
-        .iter()
-        .filter(|report| is_report_safe(report))
-        .count()
-        .try_into()
-        .unwrap()
+    SafetyPolicy::part_1().count_safe(reports)
+}
+
+pub fn find_removal(report: &[i32]) -> Option<usize> {
+    SafetyPolicy::part_2().find_removal(report)
+}
+
+pub fn is_report_safe_dampener(report: &[i32]) -> bool {
+    is_report_safe(report) || find_removal(report).is_some()
 }
 
 pub fn count_safe_reports_dampener(reports: &[Vec<i32>]) -> i32 {
+    SafetyPolicy::part_2().count_safe(reports)
+}
+
+pub fn count_safe_reports_dampener_naive(reports: &[Vec<i32>]) -> i32 {
     let mut count = 0;
     for report in reports {
         if is_report_safe(report) {
@@ -80,6 +322,7 @@ pub fn count_safe_reports_dampener(reports: &[Vec<i32>]) -> i32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Lcg;
 
     #[test]
     fn test_is_report_safe() {
@@ -89,10 +332,10 @@ mod tests {
         let list4 = vec![1, 3, 2, 4, 5];
         let list5 = vec![8, 6, 4, 4, 1];
         let list6 = vec![1, 3, 6, 7, 9];
-        let lists = vec![list1, list2, list3, list4, list5, list6];
-        let expected = vec![true, false, false, false, false, true];
+        let lists = [list1, list2, list3, list4, list5, list6];
+        let expected = [true, false, false, false, false, true];
         for (list, expected_return) in lists.iter().zip(expected.iter()) {
-            let result = is_report_safe(&list);
+            let result = is_report_safe(list);
             assert_eq!(
                 result, *expected_return,
                 "Failed is report safe on report {:?}",
@@ -135,13 +378,256 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_find_removal() {
+        assert_eq!(find_removal(&[1, 3, 2, 4, 5]), Some(1));
+        assert_eq!(find_removal(&[8, 6, 4, 4, 1]), Some(2));
+        assert_eq!(find_removal(&[1, 2, 7, 8, 9]), None);
+        assert_eq!(find_removal(&[9, 7, 6, 2, 1]), None);
+        assert_eq!(find_removal(&[7, 6, 4, 2, 1]), Some(0));
+        assert_eq!(find_removal(&[5, 1, 2, 3]), Some(0));
+        assert_eq!(find_removal(&[1, 2, 3, 10]), Some(3));
+        assert_eq!(find_removal(&[1, 2]), None);
+    }
+
+    fn is_safe_without_brute_force(report: &[i32], i: usize) -> bool {
+        let mut report_without_i = report.to_vec();
+        report_without_i.remove(i);
+        is_report_safe(&report_without_i)
+    }
+
+    fn check_against_brute_force(report: &[i32]) {
+        let rescuable = (0..report.len()).any(|i| is_safe_without_brute_force(report, i));
+        let removal = find_removal(report);
+        assert_eq!(
+            removal.is_some(),
+            rescuable,
+            "Find removal disagrees with brute force on {:?}",
+            report
+        );
+        if let Some(idx) = removal {
+            assert!(
+                is_safe_without_brute_force(report, idx),
+                "Removing {} does not fix {:?}",
+                idx,
+                report
+            );
+        }
+        assert_eq!(
+            is_report_safe_dampener(report),
+            is_report_safe(report) || rescuable,
+            "Dampener disagrees with brute force on {:?}",
+            report
+        );
+    }
+
+    #[test]
+    fn test_dampener_exhaustive() {
+        let values = [0, 1, 2, 3, 4, 7];
+        for len in 0..=6u32 {
+            for code in 0..values.len().pow(len) {
+                let mut code = code;
+                let report: Vec<i32> = (0..len)
+                    .map(|_| {
+                        let value = values[code % values.len()];
+                        code /= values.len();
+                        value
+                    })
+                    .collect();
+                check_against_brute_force(&report);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dampener_random() {
+        let mut rng = Lcg::new(1);
+        let mut next = |bound: u64| rng.next_below(bound);
+        for _ in 0..20_000 {
+            let len = 2 + next(14) as usize;
+            let mut level = next(50) as i32;
+            let direction = if next(2) == 0 { 1 } else { -1 };
+            let report: Vec<i32> = (0..len)
+                .map(|_| {
+                    level += match next(10) {
+                        0 => 0,
+                        1 => direction * (4 + next(3) as i32),
+                        2 => -direction * (1 + next(3) as i32),
+                        _ => direction * (1 + next(3) as i32),
+                    };
+                    level
+                })
+                .collect();
+            check_against_brute_force(&report);
+        }
+    }
+
+    fn is_safe_with_removals_brute_force(policy: &SafetyPolicy, report: &[i32]) -> bool {
+        (0..1u32 << report.len()).any(|removed: u32| {
+            if removed.count_ones() as usize > policy.max_removals {
+                return false;
+            }
+            let kept: Vec<i32> = (0..report.len())
+                .filter(|&i| removed & (1 << i) == 0)
+                .map(|i| report[i])
+                .collect();
+            kept.len() >= 2
+                && policy.directions().iter().any(|&direction| {
+                    kept.windows(2)
+                        .all(|pair| policy.is_step_safe(pair[0], pair[1], direction))
+                })
+        })
+    }
+
+    #[test]
+    fn test_safety_policy() {
+        let reports = vec![
+            vec![7, 6, 4, 2, 1],
+            vec![1, 2, 7, 8, 9],
+            vec![9, 7, 6, 2, 1],
+            vec![1, 3, 2, 4, 5],
+            vec![8, 6, 4, 4, 1],
+            vec![1, 3, 6, 7, 9],
+        ];
+        assert_eq!(SafetyPolicy::part_1().count_safe(&reports), 2);
+        assert_eq!(SafetyPolicy::part_2().count_safe(&reports), 4);
+        assert_eq!(SafetyPolicy::part_2().min_removals(&reports[3]), Some(1));
+        assert_eq!(SafetyPolicy::part_2().min_removals(&reports[0]), Some(0));
+
+        let mut policy = SafetyPolicy::part_1();
+        policy.max_removals = 2;
+        assert_eq!(policy.min_removals(&[1, 2, 7, 8, 9]), Some(2));
+        assert_eq!(policy.min_removals(&[1, 5, 9, 13, 17]), None);
+        assert_eq!(policy.min_removals(&[1, 9, 2, 9, 3]), Some(2));
+        policy.max_step = 5;
+        assert_eq!(policy.min_removals(&[1, 2, 7, 8, 9]), Some(0));
+
+        let policy = SafetyPolicy {
+            min_step: 0,
+            max_step: 2,
+            monotonic: false,
+            max_removals: 0,
+        };
+        assert!(policy.is_safe(&[1, 3, 3, 2, 4]));
+        assert!(!policy.is_safe(&[1, 4]));
+        assert!(!policy.is_safe(&[1]));
+    }
+
+    #[test]
+    fn test_safety_policy_against_brute_force() {
+        let policies = [
+            SafetyPolicy::part_1(),
+            SafetyPolicy::part_2(),
+            SafetyPolicy {
+                max_removals: 2,
+                ..SafetyPolicy::part_1()
+            },
+            SafetyPolicy {
+                max_removals: 3,
+                ..SafetyPolicy::part_1()
+            },
+            SafetyPolicy {
+                min_step: 0,
+                max_step: 2,
+                monotonic: false,
+                max_removals: 2,
+            },
+        ];
+        let values = [0, 1, 2, 3, 5, 8];
+        for len in 0..=6u32 {
+            for code in 0..values.len().pow(len) {
+                let mut code = code;
+                let report: Vec<i32> = (0..len)
+                    .map(|_| {
+                        let value = values[code % values.len()];
+                        code /= values.len();
+                        value
+                    })
+                    .collect();
+                for policy in &policies {
+                    assert_eq!(
+                        policy.is_safe(&report),
+                        is_safe_with_removals_brute_force(policy, &report),
+                        "Policy {:?} disagrees with brute force on {:?}",
+                        policy,
+                        report
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_report_verdict() {
+        let policy = SafetyPolicy::part_1();
+        let verdict = |report: &[i32]| {
+            let verdict = policy.verdict(report);
+            (verdict.failure.map(|f| (f.kind, f.index)), verdict.rescue)
+        };
+        assert_eq!(verdict(&[7, 6, 4, 2, 1]), (None, None));
+        assert_eq!(
+            verdict(&[1, 2, 7, 8, 9]),
+            (Some((FailureKind::StepTooLarge, 1)), None)
+        );
+        assert_eq!(
+            verdict(&[9, 7, 6, 2, 1]),
+            (Some((FailureKind::StepTooLarge, 2)), None)
+        );
+        assert_eq!(
+            verdict(&[1, 3, 2, 4, 5]),
+            (Some((FailureKind::DirectionChange, 1)), Some(1))
+        );
+        assert_eq!(
+            verdict(&[8, 6, 4, 4, 1]),
+            (Some((FailureKind::ZeroStep, 2)), Some(2))
+        );
+        assert_eq!(verdict(&[5]), (Some((FailureKind::TooShort, 0)), None));
+        assert_eq!(
+            verdict(&[3, 3, 4, 5]),
+            (Some((FailureKind::ZeroStep, 0)), Some(0))
+        );
+        assert!(policy.verdict(&[1, 3, 2, 4, 5]).is_rescued());
+        assert!(!policy.verdict(&[7, 6, 4, 2, 1]).is_rescued());
+
+        let reports = read_input("data/day02.txt").expect("Failed to read test input");
+        for report in &reports {
+            let verdict = policy.verdict(report);
+            assert_eq!(verdict.is_safe(), policy.is_safe(report), "{:?}", report);
+            assert_eq!(
+                verdict.is_safe() || verdict.is_rescued(),
+                SafetyPolicy::part_2().is_safe(report),
+                "{:?}",
+                report
+            );
+        }
+    }
+
     #[test]
     fn test_day02_part1_and_part2() {
         let reports = read_input("data/day02.txt").expect("Failed to read test input");
         let result_1 = count_safe_reports(&reports);
         let result_2 = count_safe_reports_dampener(&reports);
 
-        assert_eq!(result_1, 306, "Day 02 - Part 1 failed"); 
-        assert_eq!(result_2, 366, "Day 02 - Part 2 failed"); 
+        assert_eq!(result_1, 306, "Day 02 - Part 1 failed");
+        assert_eq!(result_2, 366, "Day 02 - Part 2 failed");
+        assert_eq!(
+            count_safe_reports_dampener_naive(&reports),
+            366,
+            "Day 02 - Part 2 naive failed"
+        );
+        let policy_k2 = SafetyPolicy {
+            max_removals: 2,
+            ..SafetyPolicy::part_2()
+        };
+        let result_k2 = policy_k2.count_safe(&reports);
+        assert_eq!(result_k2, 599, "Day 02 - k = 2 failed");
+        assert_eq!(
+            reports
+                .iter()
+                .filter(|report| is_safe_with_removals_brute_force(&policy_k2, report))
+                .count(),
+            599,
+            "Day 02 - k = 2 brute force failed"
+        );
     }
 }
@@ -1,6 +1,10 @@
+use num_bigint::BigUint;
 use regex::Regex;
 use std::error::Error;
+use std::fmt;
 use std::fs;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
 pub fn run() {
@@ -65,15 +69,556 @@ pub fn compute_enabled_multiplications(corrupted_memory: &[String]) -> i32 {
     total
 }
 
+pub const MAX_OPERANDS: usize = 4;
+pub const MAX_NAME_LEN: usize = 16;
+pub const DEFAULT_OPERAND_DIGITS: u8 = 3;
+pub const MAX_OPERAND_DIGITS: u8 = 19;
+// A name, its parenthesis and the longest operand list with separators.
+const MAX_PENDING: usize = MAX_NAME_LEN + 1 + MAX_OPERANDS * (MAX_OPERAND_DIGITS as usize + 1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operands {
+    values: [u64; MAX_OPERANDS],
+    len: usize,
+}
+
+impl Operands {
+    pub fn from_slice(values: &[u64]) -> Self {
+        let mut operands = Operands {
+            values: [0; MAX_OPERANDS],
+            len: values.len(),
+        };
+        operands.values[..values.len()].copy_from_slice(values);
+        operands
+    }
+
+    pub fn as_slice(&self) -> &[u64] {
+        &self.values[..self.len]
+    }
+
+    fn push(&mut self, value: u64) {
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Mul,
+    Add,
+    Do,
+    Dont,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(Operands),
+    Add(Operands),
+    Do,
+    Dont,
+}
+
+impl Instruction {
+    fn new(opcode: Opcode, operands: Operands) -> Self {
+        match opcode {
+            Opcode::Mul => Instruction::Mul(operands),
+            Opcode::Add => Instruction::Add(operands),
+            Opcode::Do => Instruction::Do,
+            Opcode::Dont => Instruction::Dont,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, operands) = match self {
+            Instruction::Mul(operands) => ("mul", operands.as_slice()),
+            Instruction::Add(operands) => ("add", operands.as_slice()),
+            Instruction::Do => ("do", &[][..]),
+            Instruction::Dont => ("don't", &[][..]),
+        };
+        let operands: Vec<String> = operands.iter().map(u64::to_string).collect();
+        write!(f, "{}({})", name, operands.join(","))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcodeSpec {
+    pub name: String,
+    pub opcode: Opcode,
+    pub min_args: usize,
+    pub max_args: usize,
+}
+
+impl OpcodeSpec {
+    pub fn new(name: &str, opcode: Opcode, min_args: usize, max_args: usize) -> Self {
+        OpcodeSpec {
+            name: name.to_string(),
+            opcode,
+            min_args,
+            max_args,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcodeTable {
+    specs: Vec<OpcodeSpec>,
+}
+
+impl OpcodeTable {
+    pub fn new(specs: Vec<OpcodeSpec>) -> Result<Self, String> {
+        if specs.len() > 64 {
+            return Err("An opcode table holds at most 64 opcodes".to_string());
+        }
+        for (idx, spec) in specs.iter().enumerate() {
+            if spec.name.is_empty()
+                || spec.name.len() > MAX_NAME_LEN
+                || spec
+                    .name
+                    .bytes()
+                    .any(|byte| matches!(byte, b'(' | b')' | b',' | b'0'..=b'9') || is_space(byte))
+            {
+                return Err(format!("Invalid opcode name '{}'", spec.name));
+            }
+            if spec.min_args > spec.max_args || spec.max_args > MAX_OPERANDS {
+                return Err(format!("Invalid arity for opcode '{}'", spec.name));
+            }
+            if specs[..idx].iter().any(|other| other.name == spec.name) {
+                return Err(format!("Duplicate opcode '{}'", spec.name));
+            }
+        }
+        Ok(OpcodeTable { specs })
+    }
+
+    pub fn puzzle() -> Self {
+        OpcodeTable::new(vec![
+            OpcodeSpec::new("mul", Opcode::Mul, 2, 2),
+            OpcodeSpec::new("do", Opcode::Do, 0, 0),
+            OpcodeSpec::new("don't", Opcode::Dont, 0, 0),
+        ])
+        .unwrap()
+    }
+
+    pub fn extended() -> Self {
+        OpcodeTable::new(vec![
+            OpcodeSpec::new("mul", Opcode::Mul, 2, MAX_OPERANDS),
+            OpcodeSpec::new("add", Opcode::Add, 2, MAX_OPERANDS),
+            OpcodeSpec::new("do", Opcode::Do, 0, 0),
+            OpcodeSpec::new("don't", Opcode::Dont, 0, 0),
+        ])
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    // Bytes of a name matched so far and the bitset of opcodes still matching them.
+    Name(usize, u64),
+    Open(usize, Operands),
+    Digits(usize, Operands, u64, u8),
+    Separator(usize, Operands),
+}
+
+// Same set as the regex `\s`, restricted to ASCII.
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r')
+}
+
+struct Lexer {
+    table: OpcodeTable,
+    max_digits: u8,
+    state: State,
+    start: u64,
+    // Bytes accepted since the name started, without the spaces after separators.
+    pending: [u8; MAX_PENDING],
+    pending_len: usize,
+}
+
+impl Lexer {
+    fn starting_with(&self, byte: u8) -> u64 {
+        self.table
+            .specs
+            .iter()
+            .enumerate()
+            .filter(|(_, spec)| spec.name.as_bytes()[0] == byte)
+            .fold(0, |candidates, (idx, _)| candidates | 1 << idx)
+    }
+
+    // A shorter name may start inside the matched one. Names hold no parentheses, digits,
+    // separators or spaces, so replayed instructions start in the name part and never complete.
+    fn replay(&mut self, byte: u8, offset: u64) -> Option<Instruction> {
+        let (pending, pending_len) = (self.pending, self.pending_len);
+        let start = self.start;
+        self.state = State::Start;
+        for (idx, &replayed) in pending[..pending_len].iter().enumerate().skip(1) {
+            self.feed(replayed, start + idx as u64);
+        }
+        self.feed(byte, offset)
+    }
+
+    fn accept(&mut self, byte: u8) {
+        self.pending[self.pending_len] = byte;
+        self.pending_len += 1;
+    }
+
+    fn feed(&mut self, byte: u8, offset: u64) -> Option<Instruction> {
+        match self.state {
+            State::Start => {
+                let candidates = self.starting_with(byte);
+                if candidates != 0 {
+                    self.state = State::Name(1, candidates);
+                    self.start = offset;
+                    self.pending_len = 0;
+                    self.accept(byte);
+                }
+                None
+            }
+            State::Name(len, candidates) => {
+                if byte == b'(' {
+                    if let Some(spec) = (0..self.table.specs.len()).find(|&idx| {
+                        candidates & (1 << idx) != 0 && self.table.specs[idx].name.len() == len
+                    }) {
+                        self.state = State::Open(spec, Operands::from_slice(&[]));
+                        self.accept(byte);
+                        return None;
+                    }
+                }
+                let next = (0..self.table.specs.len())
+                    .filter(|&idx| {
+                        candidates & (1 << idx) != 0
+                            && self.table.specs[idx].name.as_bytes().get(len) == Some(&byte)
+                    })
+                    .fold(0, |next, idx| next | 1 << idx);
+                if next != 0 {
+                    self.state = State::Name(len + 1, next);
+                    self.accept(byte);
+                    return None;
+                }
+                self.replay(byte, offset)
+            }
+            State::Open(spec, operands) => match byte {
+                b'0'..=b'9' if self.table.specs[spec].max_args > 0 => {
+                    self.state = State::Digits(spec, operands, (byte - b'0') as u64, 1);
+                    self.accept(byte);
+                    None
+                }
+                b')' if self.table.specs[spec].min_args == 0 => self.finish(spec, operands),
+                _ => self.replay(byte, offset),
+            },
+            State::Digits(spec, mut operands, value, digits) => match byte {
+                b'0'..=b'9' if digits < self.max_digits => {
+                    self.state = State::Digits(
+                        spec,
+                        operands,
+                        value * 10 + (byte - b'0') as u64,
+                        digits + 1,
+                    );
+                    self.accept(byte);
+                    None
+                }
+                b',' if operands.len + 1 < self.table.specs[spec].max_args => {
+                    operands.push(value);
+                    self.state = State::Separator(spec, operands);
+                    self.accept(byte);
+                    None
+                }
+                b')' if operands.len + 1 >= self.table.specs[spec].min_args => {
+                    operands.push(value);
+                    self.finish(spec, operands)
+                }
+                _ => self.replay(byte, offset),
+            },
+            State::Separator(spec, operands) => match byte {
+                byte if is_space(byte) => None,
+                b'0'..=b'9' => {
+                    self.state = State::Digits(spec, operands, (byte - b'0') as u64, 1);
+                    self.accept(byte);
+                    None
+                }
+                _ => self.replay(byte, offset),
+            },
+        }
+    }
+
+    fn finish(&mut self, spec: usize, operands: Operands) -> Option<Instruction> {
+        self.state = State::Start;
+        Some(Instruction::new(self.table.specs[spec].opcode, operands))
+    }
+}
+
+// Yields each instruction with the byte offset where its name starts.
+pub struct Scanner<R> {
+    reader: R,
+    lexer: Lexer,
+    offset: u64,
+}
+
+impl<R: BufRead> Scanner<R> {
+    pub fn new(reader: R) -> Self {
+        Scanner::with_table(reader, OpcodeTable::puzzle())
+    }
+
+    pub fn with_table(reader: R, table: OpcodeTable) -> Self {
+        Scanner {
+            reader,
+            lexer: Lexer {
+                table,
+                max_digits: DEFAULT_OPERAND_DIGITS,
+                state: State::Start,
+                start: 0,
+                pending: [0; MAX_PENDING],
+                pending_len: 0,
+            },
+            offset: 0,
+        }
+    }
+
+    pub fn with_max_digits(mut self, max_digits: u8) -> Result<Self, String> {
+        if max_digits == 0 || max_digits > MAX_OPERAND_DIGITS {
+            return Err(format!(
+                "Operands must allow between 1 and {} digits, got {}",
+                MAX_OPERAND_DIGITS, max_digits
+            ));
+        }
+        self.lexer.max_digits = max_digits;
+        Ok(self)
+    }
+}
+
+impl<R: BufRead> Iterator for Scanner<R> {
+    type Item = io::Result<(u64, Instruction)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let buffer = match self.reader.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            if buffer.is_empty() {
+                return None;
+            }
+            let mut found = None;
+            let mut consumed = buffer.len();
+            for (idx, &byte) in buffer.iter().enumerate() {
+                if let Some(instruction) = self.lexer.feed(byte, self.offset + idx as u64) {
+                    found = Some((self.lexer.start, instruction));
+                    consumed = idx + 1;
+                    break;
+                }
+            }
+            self.reader.consume(consumed);
+            self.offset += consumed as u64;
+            if let Some(found) = found {
+                return Some(Ok(found));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub offset: u64,
+    pub instruction: Instruction,
+    pub executed: bool,
+}
+
+pub trait Accumulator: Clone + fmt::Display + fmt::Debug {
+    fn zero() -> Self;
+    fn from_operand(value: u64) -> Option<Self>;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_accumulator {
+    ($($t:ty),*) => {
+        $(
+            impl Accumulator for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn from_operand(value: u64) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *rhs)
+                }
+
+                fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                    <$t>::checked_mul(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_accumulator!(i32, i64, u64, i128, u128);
+
+impl Accumulator for BigUint {
+    fn zero() -> Self {
+        BigUint::from(0u32)
+    }
+
+    fn from_operand(value: u64) -> Option<Self> {
+        Some(BigUint::from(value))
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub offset: u64,
+    pub instruction: Instruction,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Accumulator overflow at byte {} executing {}",
+            self.offset, self.instruction
+        )
+    }
+}
+
+impl Error for Overflow {}
+
+// An unconditional machine (part 1) ignores `do()` and `don't()`.
+pub struct Machine<A = i64> {
+    pub enabled: bool,
+    pub accumulator: A,
+    conditional: bool,
+    trace: Option<Vec<TraceEntry>>,
+}
+
+impl<A: Accumulator> Default for Machine<A> {
+    fn default() -> Self {
+        Machine {
+            enabled: true,
+            accumulator: A::zero(),
+            conditional: true,
+            trace: None,
+        }
+    }
+}
+
+impl Machine {
+    pub fn new() -> Self {
+        Machine::default()
+    }
+
+    pub fn unconditional() -> Self {
+        Machine::default().conditional(false)
+    }
+}
+
+impl<A: Accumulator> Machine<A> {
+    pub fn conditional(mut self, conditional: bool) -> Self {
+        self.conditional = conditional;
+        self
+    }
+
+    pub fn traced(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or(&[])
+    }
+
+    fn evaluate(instruction: &Instruction) -> Option<A> {
+        match instruction {
+            Instruction::Mul(operands) => operands
+                .as_slice()
+                .iter()
+                .try_fold(A::from_operand(1)?, |product, &x| {
+                    product.checked_mul(&A::from_operand(x)?)
+                }),
+            Instruction::Add(operands) => operands
+                .as_slice()
+                .iter()
+                .try_fold(A::zero(), |sum, &x| sum.checked_add(&A::from_operand(x)?)),
+            Instruction::Do | Instruction::Dont => Some(A::zero()),
+        }
+    }
+
+    pub fn execute(&mut self, offset: u64, instruction: Instruction) -> Result<(), Overflow> {
+        let executed = match instruction {
+            Instruction::Do | Instruction::Dont if !self.conditional => false,
+            Instruction::Do => {
+                self.enabled = true;
+                true
+            }
+            Instruction::Dont => {
+                self.enabled = false;
+                true
+            }
+            _ if !self.enabled => false,
+            Instruction::Mul(_) | Instruction::Add(_) => {
+                self.accumulator = Self::evaluate(&instruction)
+                    .and_then(|value| self.accumulator.checked_add(&value))
+                    .ok_or(Overflow {
+                        offset,
+                        instruction,
+                    })?;
+                true
+            }
+        };
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEntry {
+                offset,
+                instruction,
+                executed,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn run<R: BufRead>(&mut self, scanner: Scanner<R>) -> Result<A, Box<dyn Error>> {
+        for item in scanner {
+            let (offset, instruction) = item?;
+            self.execute(offset, instruction)?;
+        }
+        Ok(self.accumulator.clone())
+    }
+}
+
+pub fn sum_multiplications<R: BufRead>(reader: R) -> Result<i64, Box<dyn Error>> {
+    Machine::unconditional().run(Scanner::new(reader))
+}
+
+pub fn sum_enabled_multiplications<R: BufRead>(reader: R) -> Result<i64, Box<dyn Error>> {
+    Machine::new().run(Scanner::new(reader))
+}
+
+pub fn open_input(path: &str) -> Result<BufReader<File>, Box<dyn Error>> {
+    let file = File::open(Path::new(path))
+        .map_err(|e| format!("Failed to open input file '{}': {}", path, e))?;
+    Ok(BufReader::new(file))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Lcg;
 
     #[test]
     fn test_find_valid_mul() {
         let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
         let expected = vec![(2, 4), (5, 5), (11, 8), (8, 5)];
-        let result = find_valid_mul(&input);
+        let result = find_valid_mul(input);
         assert_eq!(
             result, expected,
             "Failed find valid mul, expected {:?} got {:?}",
@@ -112,13 +657,279 @@ mod tests {
         );
     }
 
+    fn mul(x: u64, y: u64) -> Instruction {
+        Instruction::Mul(Operands::from_slice(&[x, y]))
+    }
+
+    fn scan(input: &str, table: OpcodeTable) -> Vec<(u64, Instruction)> {
+        Scanner::with_table(input.as_bytes(), table)
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scanner_instructions() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(
+            scan(input, OpcodeTable::puzzle()),
+            vec![
+                (1, mul(2, 4)),
+                (20, Instruction::Dont),
+                (28, mul(5, 5)),
+                (48, mul(11, 8)),
+                (59, Instruction::Do),
+                (64, mul(8, 5)),
+            ]
+        );
+        let input = "mmul(1,2)mul(1234,5)mul(12,\t 3)dodo()don'don't()mul(1,2,3)";
+        let instructions: Vec<Instruction> = scan(input, OpcodeTable::puzzle())
+            .into_iter()
+            .map(|(_, instruction)| instruction)
+            .collect();
+        assert_eq!(
+            instructions,
+            vec![mul(1, 2), mul(12, 3), Instruction::Do, Instruction::Dont]
+        );
+    }
+
+    #[test]
+    fn test_scanner_extended_opcodes() {
+        let input = "mul(1,2,3)add(4,5)aadd(1,2)ado()mul(9)add(1,2,3,4,5)mul(2,3,4,5)";
+        let instructions: Vec<String> = scan(input, OpcodeTable::extended())
+            .iter()
+            .map(|(offset, instruction)| format!("{}@{}", instruction, offset))
+            .collect();
+        assert_eq!(
+            instructions,
+            vec![
+                "mul(1,2,3)@0",
+                "add(4,5)@10",
+                "add(1,2)@19",
+                "do()@28",
+                "mul(2,3,4,5)@52"
+            ]
+        );
+
+        // "nop" overlaps "no" after its first byte, so the prefix has to be replayed
+        let table = OpcodeTable::new(vec![
+            OpcodeSpec::new("onop", Opcode::Do, 0, 0),
+            OpcodeSpec::new("nop", Opcode::Dont, 0, 0),
+        ])
+        .unwrap();
+        assert_eq!(
+            scan("onnop()onop()", table),
+            vec![(2, Instruction::Dont), (7, Instruction::Do)]
+        );
+
+        // "mul" ends where "xmul" does, so it is replayed when the operands only fit "mul"
+        let render = |input: &str, table: OpcodeTable| -> Vec<String> {
+            scan(input, table)
+                .iter()
+                .map(|(offset, instruction)| format!("{}@{}", instruction, offset))
+                .collect()
+        };
+        let table = OpcodeTable::new(vec![
+            OpcodeSpec::new("xmul", Opcode::Add, 1, 1),
+            OpcodeSpec::new("mul", Opcode::Mul, 2, 2),
+        ])
+        .unwrap();
+        assert_eq!(
+            render("xmul(2,3)xmul(4)xmul( 5)mul(6,7)xmul(8, 9)", table),
+            vec!["mul(2,3)@1", "add(4)@9", "mul(6,7)@24", "mul(8,9)@33"]
+        );
+        let table = OpcodeTable::new(vec![
+            OpcodeSpec::new("xmul", Opcode::Add, 2, 2),
+            OpcodeSpec::new("mul", Opcode::Mul, 1, 1),
+        ])
+        .unwrap();
+        assert_eq!(
+            render("xmul(7)xmul(1,2)xmul()", table),
+            vec!["mul(7)@1", "add(1,2)@7"]
+        );
+
+        // Replays a long name followed by three full-width operands
+        let long_name = "x".repeat(MAX_NAME_LEN);
+        let table = OpcodeTable::new(vec![
+            OpcodeSpec::new(&long_name, Opcode::Add, 3, 3),
+            OpcodeSpec::new(&long_name[1..], Opcode::Mul, MAX_OPERANDS, MAX_OPERANDS),
+        ])
+        .unwrap();
+        let operand = "9".repeat(MAX_OPERAND_DIGITS as usize);
+        let input = format!(
+            "{}({},{},{},{})",
+            long_name, operand, operand, operand, operand
+        );
+        let scanner = Scanner::with_table(input.as_bytes(), table)
+            .with_max_digits(MAX_OPERAND_DIGITS)
+            .unwrap();
+        let instructions: Vec<(u64, Instruction)> = scanner.map(|item| item.unwrap()).collect();
+        let value = 10u64.pow(MAX_OPERAND_DIGITS as u32) - 1;
+        assert_eq!(
+            instructions,
+            vec![(
+                1,
+                Instruction::Mul(Operands::from_slice(&[value; MAX_OPERANDS]))
+            )]
+        );
+        let too_long = "x".repeat(MAX_NAME_LEN + 1);
+        assert!(OpcodeTable::new(vec![OpcodeSpec::new(&too_long, Opcode::Do, 0, 0)]).is_err());
+
+        assert!(OpcodeTable::new(vec![OpcodeSpec::new("mu(l", Opcode::Mul, 2, 2)]).is_err());
+        assert!(OpcodeTable::new(vec![OpcodeSpec::new("mul", Opcode::Mul, 3, 2)]).is_err());
+        assert!(OpcodeTable::new(vec![OpcodeSpec::new("mul", Opcode::Mul, 2, 9)]).is_err());
+    }
+
+    #[test]
+    fn test_machine_trace() {
+        let input = "mul(2,4)don't()mul(5,5)do()add(1,2)mul(3,3)";
+        let mut machine = Machine::new().traced();
+        let scanner = Scanner::with_table(input.as_bytes(), OpcodeTable::extended());
+        assert_eq!(machine.run(scanner).unwrap(), 8 + 3 + 9);
+        let trace: Vec<(u64, bool)> = machine
+            .trace()
+            .iter()
+            .map(|entry| (entry.offset, entry.executed))
+            .collect();
+        assert_eq!(
+            trace,
+            vec![
+                (0, true),
+                (8, true),
+                (15, false),
+                (23, true),
+                (27, true),
+                (35, true)
+            ]
+        );
+        assert_eq!(machine.trace()[2].instruction, mul(5, 5));
+
+        let mut machine = Machine::unconditional().traced();
+        assert_eq!(
+            machine.run(Scanner::new(input.as_bytes())).unwrap(),
+            8 + 25 + 9
+        );
+        assert!(!machine.trace()[1].executed);
+        assert!(Machine::new().trace().is_empty());
+    }
+
+    #[test]
+    fn test_scanner_across_boundaries() {
+        let input = "mul(12,\n 34)do\n()mul(5,6)don't()mul(7,8)";
+        for capacity in 1..8 {
+            let reader = BufReader::with_capacity(capacity, input.as_bytes());
+            assert_eq!(
+                sum_multiplications(reader).unwrap(),
+                12 * 34 + 30 + 56,
+                "Streaming sum failed with buffer of {} bytes",
+                capacity
+            );
+            let reader = BufReader::with_capacity(capacity, input.as_bytes());
+            assert_eq!(sum_enabled_multiplications(reader).unwrap(), 12 * 34 + 30);
+        }
+    }
+
+    #[test]
+    fn test_scanner_matches_regex() {
+        let fragments = [
+            "mul(", "do()", "don't()", "do", "1", "23", "456", "7890", ",", ", ", ",\t", ")", "x",
+            "m", "d", "n't(",
+        ];
+        let mut rng = Lcg::new(1);
+        let mut matched = 0;
+        for _ in 0..500 {
+            let line: String = (0..60)
+                .map(|_| fragments[rng.next_below(fragments.len() as u64) as usize])
+                .collect();
+            matched += Scanner::new(line.as_bytes()).count();
+            let lines = vec![line.clone()];
+            assert_eq!(
+                sum_multiplications(line.as_bytes()).unwrap(),
+                compute_multiplications(&lines) as i64,
+                "Scanner and regex disagree on {:?}",
+                line
+            );
+            assert_eq!(
+                sum_enabled_multiplications(line.as_bytes()).unwrap(),
+                compute_enabled_multiplications(&lines) as i64,
+                "Scanner and regex disagree on {:?}",
+                line
+            );
+        }
+        assert!(
+            matched > 1000,
+            "Generated lines should contain instructions"
+        );
+    }
+
+    #[test]
+    fn test_operand_digits() {
+        let input = "mul(1234,5)mul(123456,2)mul(12345678901234567890,1)";
+        let scanner = Scanner::new(input.as_bytes());
+        assert_eq!(scanner.count(), 0);
+
+        let scanner = Scanner::new(input.as_bytes()).with_max_digits(6).unwrap();
+        let instructions: Vec<Instruction> = scanner.map(|item| item.unwrap().1).collect();
+        assert_eq!(instructions, vec![mul(1234, 5), mul(123456, 2)]);
+
+        let scanner = Scanner::new(input.as_bytes()).with_max_digits(19).unwrap();
+        assert_eq!(scanner.count(), 2);
+        assert!(Scanner::new(input.as_bytes()).with_max_digits(0).is_err());
+        assert!(Scanner::new(input.as_bytes()).with_max_digits(20).is_err());
+    }
+
+    #[test]
+    fn test_accumulator_overflow() {
+        let input = "mul(46341,46341)xmul(65536,32768)";
+        let scanner = Scanner::new(input.as_bytes()).with_max_digits(5).unwrap();
+        let mut machine = Machine::<i32>::default();
+        let error = machine.run(scanner).unwrap_err();
+        let overflow = error
+            .downcast_ref::<Overflow>()
+            .expect("Expected an overflow");
+        assert_eq!(overflow.offset, 0);
+        assert_eq!(overflow.instruction, mul(46341, 46341));
+        assert_eq!(
+            error.to_string(),
+            "Accumulator overflow at byte 0 executing mul(46341,46341)"
+        );
+
+        let input = "mul(999999999,999999999)mul(9999999999999999999,9999999999999999999)";
+        let scanner = Scanner::new(input.as_bytes()).with_max_digits(19).unwrap();
+        let error = Machine::new().run(scanner).unwrap_err();
+        assert_eq!(error.downcast_ref::<Overflow>().unwrap().offset, 24);
+
+        let scanner = Scanner::new(input.as_bytes()).with_max_digits(19).unwrap();
+        let expected = 999999999u128.pow(2) + 9999999999999999999u128.pow(2);
+        assert_eq!(Machine::<u128>::default().run(scanner).unwrap(), expected);
+
+        let scanner = Scanner::new(input.as_bytes()).with_max_digits(19).unwrap();
+        let total = Machine::<BigUint>::default().run(scanner).unwrap();
+        assert_eq!(total, BigUint::from(expected));
+
+        let scanner = Scanner::new("mul(2,3)".as_bytes());
+        assert!(Machine::<u64>::default()
+            .conditional(false)
+            .run(scanner)
+            .is_ok());
+    }
+
     #[test]
     fn test_day03_part1_and_part2() {
         let corrupted_memory = read_input("data/day03.txt").expect("Failed to read test input");
         let result_1 = compute_multiplications(&corrupted_memory);
         let result_2 = compute_enabled_multiplications(&corrupted_memory);
 
-        assert_eq!(result_1, 188116424, "Day 03 - Part 1 failed"); 
-        assert_eq!(result_2, 104245808, "Day 03 - Part 2 failed"); 
+        assert_eq!(result_1, 188116424, "Day 03 - Part 1 failed");
+        assert_eq!(result_2, 104245808, "Day 03 - Part 2 failed");
+
+        let reader = open_input("data/day03.txt").expect("Failed to open test input");
+        assert_eq!(sum_multiplications(reader).unwrap(), 188116424);
+        let reader = open_input("data/day03.txt").expect("Failed to open test input");
+        assert_eq!(sum_enabled_multiplications(reader).unwrap(), 104245808);
+        let reader = open_input("data/day03.txt").expect("Failed to open test input");
+        let result = Machine::<i32>::default()
+            .conditional(false)
+            .run(Scanner::new(reader));
+        assert_eq!(result.unwrap(), 188116424);
     }
 }
@@ -131,7 +131,7 @@ mod tests {
     fn test_count_xmas_samx() {
         let input = "XMASAMX.MM";
         let expected = 2;
-        let result = count_xmas_samx(&input);
+        let result = count_xmas_samx(input);
         assert_eq!(
             result, expected,
             "Failed count xmas samx, expected {:?} got {:?}",
@@ -191,7 +191,7 @@ mod tests {
         let result_1 = count_all_xmas(&puzzle);
         let result_2 = count_all_x_mas(&puzzle);
 
-        assert_eq!(result_1, 2569, "Day 04 - Part 1 failed"); 
-        assert_eq!(result_2, 1998, "Day 04 - Part 2 failed"); 
+        assert_eq!(result_1, 2569, "Day 04 - Part 1 failed");
+        assert_eq!(result_2, 1998, "Day 04 - Part 2 failed");
     }
 }
@@ -1,6 +1,7 @@
 use regex::Regex;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
+use std::fmt::Write;
 use std::fs;
 use std::path::Path;
 
@@ -154,6 +155,163 @@ pub fn reordered_middle_page_sum(updates: &[Vec<u32>], rules: &Rules) -> u32 {
         .sum()
 }
 
+const MAX_PAGES: usize = 128;
+
+pub struct RuleMatrix {
+    successors: [u128; MAX_PAGES],
+}
+
+impl RuleMatrix {
+    pub fn from_rules(rules: &Rules) -> Result<Self, String> {
+        let mut successors = [0u128; MAX_PAGES];
+        for (&before, afters) in rules.iter() {
+            for &after in afters.iter() {
+                if before as usize >= MAX_PAGES || after as usize >= MAX_PAGES {
+                    return Err(format!(
+                        "Rule {}|{} exceeds the maximum page number {}",
+                        before,
+                        after,
+                        MAX_PAGES - 1
+                    ));
+                }
+                successors[before as usize] |= 1 << after;
+            }
+        }
+        Ok(RuleMatrix { successors })
+    }
+
+    pub fn precedes(&self, before: u32, after: u32) -> bool {
+        (before as usize) < MAX_PAGES
+            && (after as usize) < MAX_PAGES
+            && self.successors[before as usize] & (1 << after) != 0
+    }
+
+    // Pages outside the matrix cannot appear in any rule, so they never violate one.
+    pub fn check_update(&self, update: &[u32]) -> bool {
+        let mut seen: u128 = 0;
+        for &page in update.iter() {
+            if (page as usize) < MAX_PAGES {
+                if self.successors[page as usize] & seen != 0 {
+                    return false;
+                }
+                seen |= 1 << page;
+            }
+        }
+        true
+    }
+
+    fn successors_of(&self, page: u32) -> u128 {
+        if (page as usize) < MAX_PAGES {
+            self.successors[page as usize]
+        } else {
+            0
+        }
+    }
+
+    // Kahn's algorithm, taking the earliest page in update order that nothing left must precede.
+    pub fn reorder_update(&self, update: &[u32]) -> Vec<u32> {
+        let mut remaining: Vec<u32> = Vec::with_capacity(update.len());
+        for &page in update.iter() {
+            if !remaining.contains(&page) {
+                remaining.push(page);
+            }
+        }
+        let mut reordered = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let blocked = remaining
+                .iter()
+                .fold(0u128, |blocked, &page| blocked | self.successors_of(page));
+            let ready = remaining
+                .iter()
+                .position(|&page| page as usize >= MAX_PAGES || blocked & (1 << page) == 0);
+            match ready {
+                Some(idx) => reordered.push(remaining.remove(idx)),
+                // The remaining pages form a cycle, which the rule map version drops as well.
+                None => break,
+            }
+        }
+        reordered
+    }
+}
+
+pub fn middle_page_sum_matrix(updates: &[Vec<u32>], matrix: &RuleMatrix) -> u32 {
+    updates
+        .iter()
+        .filter(|update| matrix.check_update(update))
+        .map(|update| update[update.len() / 2])
+        .sum()
+}
+
+pub fn reordered_middle_page_sum_matrix(updates: &[Vec<u32>], matrix: &RuleMatrix) -> u32 {
+    updates
+        .iter()
+        .filter(|update| !matrix.check_update(update))
+        .map(|update| {
+            let reordered_update = matrix.reorder_update(update);
+            reordered_update[reordered_update.len() / 2]
+        })
+        .sum()
+}
+
+fn sorted_edges(rules: &Rules) -> Vec<(u32, u32)> {
+    let mut edges: Vec<(u32, u32)> = rules
+        .iter()
+        .flat_map(|(&before, afters)| afters.iter().map(move |&after| (before, after)))
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+pub fn rules_to_dot(rules: &Rules) -> String {
+    let mut dot = String::from("digraph rules {\n");
+    for (before, after) in sorted_edges(rules) {
+        writeln!(dot, "    {} -> {};", before, after).unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn update_to_dot(update: &[u32], rules: &Rules) -> String {
+    let positions: HashMap<u32, usize> = update
+        .iter()
+        .enumerate()
+        .map(|(idx, &page)| (page, idx))
+        .collect();
+    let reordered = reorder_update(update, rules);
+
+    let mut dot = String::from("digraph update {\n");
+    for (idx, &page) in reordered.iter().enumerate() {
+        writeln!(
+            dot,
+            "    {} [label=\"{}\\nposition {} -> {}\"];",
+            page, page, positions[&page], idx
+        )
+        .unwrap();
+    }
+    for (before, after) in sorted_edges(rules) {
+        if let (Some(&idx_before), Some(&idx_after)) =
+            (positions.get(&before), positions.get(&after))
+        {
+            if idx_before > idx_after {
+                writeln!(dot, "    {} -> {} [color=red, penwidth=2];", before, after).unwrap();
+            } else {
+                writeln!(dot, "    {} -> {};", before, after).unwrap();
+            }
+        }
+    }
+    for pair in reordered.windows(2) {
+        writeln!(
+            dot,
+            "    {} -> {} [color=blue, style=dashed, constraint=false];",
+            pair[0], pair[1]
+        )
+        .unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,7 +326,7 @@ mod tests {
         rules.insert(53, vec![29, 13]);
         rules.insert(29, vec![13]);
 
-        let updates = vec![
+        let updates = [
             vec![75, 47, 61, 53, 29],
             vec![97, 61, 53, 29, 13],
             vec![75, 29, 13],
@@ -176,9 +334,9 @@ mod tests {
             vec![61, 13, 29],
             vec![97, 13, 75, 29, 47],
         ];
-        let expected_results = vec![true, true, true, false, false, false];
+        let expected_results = [true, true, true, false, false, false];
         for (update, expected_result) in updates.iter().zip(expected_results.iter()) {
-            let result = check_update(&update, &rules);
+            let result = check_update(update, &rules);
             assert_eq!(
                 result, *expected_result,
                 "Check update failed for {:?}, expected {}, got {} (rules: {:?})",
@@ -224,19 +382,19 @@ mod tests {
         rules.insert(53, vec![29, 13]);
         rules.insert(29, vec![13]);
 
-        let updates = vec![
+        let updates = [
             vec![75, 97, 47, 61, 53],
             vec![61, 13, 29],
             vec![97, 13, 75, 29, 47],
         ];
-        let expected_results = vec![
+        let expected_results = [
             vec![97, 75, 47, 61, 53],
             vec![61, 29, 13],
             vec![97, 75, 47, 29, 13],
         ];
 
         for (update, expected_result) in updates.iter().zip(expected_results.iter()) {
-            let result = reorder_update(&update, &rules);
+            let result = reorder_update(update, &rules);
             assert_eq!(
                 result, *expected_result,
                 "Re-ordering failed for {:?}, expected {:?}, got {:?}",
@@ -272,14 +430,138 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rules_to_dot() {
+        let mut rules = HashMap::new();
+        rules.insert(47, vec![53, 13]);
+        rules.insert(29, vec![13]);
+
+        let expected = "digraph rules {\n    29 -> 13;\n    47 -> 13;\n    47 -> 53;\n}\n";
+        let result = rules_to_dot(&rules);
+        assert_eq!(
+            result, expected,
+            "Rules DOT export failed, expected {}, got {}",
+            expected, result
+        );
+    }
+
+    #[test]
+    fn test_update_to_dot() {
+        let mut rules = HashMap::new();
+        rules.insert(47, vec![53, 13, 61, 29]);
+        rules.insert(97, vec![13, 61, 47, 29, 53, 75]);
+        rules.insert(75, vec![29, 53, 47, 61, 13]);
+        rules.insert(61, vec![13, 53, 29]);
+        rules.insert(53, vec![29, 13]);
+        rules.insert(29, vec![13]);
+
+        let result = update_to_dot(&[61, 13, 29], &rules);
+        let expected = [
+            "digraph update {",
+            "    61 [label=\"61\\nposition 0 -> 0\"];",
+            "    29 [label=\"29\\nposition 2 -> 1\"];",
+            "    13 [label=\"13\\nposition 1 -> 2\"];",
+            "    29 -> 13 [color=red, penwidth=2];",
+            "    61 -> 13;",
+            "    61 -> 29;",
+            "    61 -> 29 [color=blue, style=dashed, constraint=false];",
+            "    29 -> 13 [color=blue, style=dashed, constraint=false];",
+            "}",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(
+            result, expected,
+            "Update DOT export failed, expected {}, got {}",
+            expected, result
+        );
+    }
+
+    #[test]
+    fn test_rule_matrix() {
+        let mut rules = HashMap::new();
+        rules.insert(47, vec![53, 13, 61, 29]);
+        rules.insert(97, vec![13, 61, 47, 29, 53, 75]);
+        rules.insert(75, vec![29, 53, 47, 61, 13]);
+        rules.insert(61, vec![13, 53, 29]);
+        rules.insert(53, vec![29, 13]);
+        rules.insert(29, vec![13]);
+        let matrix = RuleMatrix::from_rules(&rules).expect("Failed to build rule matrix");
+
+        let updates = vec![
+            vec![75, 47, 61, 53, 29],
+            vec![97, 61, 53, 29, 13],
+            vec![75, 29, 13],
+            vec![75, 97, 47, 61, 53],
+            vec![61, 13, 29],
+            vec![97, 13, 75, 29, 47],
+        ];
+        for update in updates.iter() {
+            assert_eq!(
+                matrix.check_update(update),
+                check_update(update, &rules),
+                "Rule matrix check disagrees with rule map for {:?}",
+                update
+            );
+            if !check_update(update, &rules) {
+                assert_eq!(
+                    matrix.reorder_update(update),
+                    reorder_update(update, &rules),
+                    "Rule matrix reordering disagrees with rule map for {:?}",
+                    update
+                );
+            }
+        }
+        assert_eq!(middle_page_sum_matrix(&updates, &matrix), 143);
+        assert_eq!(reordered_middle_page_sum_matrix(&updates, &matrix), 123);
+    }
+
+    #[test]
+    fn test_rule_matrix_partial_rules() {
+        let mut rules = HashMap::new();
+        rules.insert(1, vec![2]);
+        rules.insert(2, vec![3]);
+        let matrix = RuleMatrix::from_rules(&rules).expect("Failed to build rule matrix");
+
+        for update in [
+            vec![3, 1, 2],
+            vec![2, 3, 1],
+            vec![3, 9, 1, 2],
+            vec![5, 3, 4, 1],
+        ] {
+            assert_eq!(
+                matrix.reorder_update(&update),
+                reorder_update(&update, &rules),
+                "Rule matrix reordering disagrees with rule map for {:?}",
+                update
+            );
+        }
+        assert_eq!(matrix.reorder_update(&[3, 1, 2]), vec![1, 2, 3]);
+        assert_eq!(matrix.reorder_update(&[3, 9, 1, 2]), vec![9, 1, 2, 3]);
+        assert_eq!(matrix.reorder_update(&[5, 3, 4, 1]), vec![5, 3, 4, 1]);
+    }
+
+    #[test]
+    fn test_rule_matrix_out_of_range() {
+        let mut rules = HashMap::new();
+        rules.insert(47, vec![128]);
+        assert!(
+            RuleMatrix::from_rules(&rules).is_err(),
+            "Rule matrix should reject pages above the maximum"
+        );
+    }
+
     #[test]
     fn test_day05_part1_and_part2() {
-        let (rules, updates) =
-            read_input("data/day05.txt").expect("Failed to read test input");
+        let (rules, updates) = read_input("data/day05.txt").expect("Failed to read test input");
         let result_1 = middle_page_sum(&updates, &rules);
         let result_2 = reordered_middle_page_sum(&updates, &rules);
 
-        assert_eq!(result_1, 5248, "Day 05 - Part 1 failed"); 
-        assert_eq!(result_2, 4507, "Day 05 - Part 2 failed"); 
+        assert_eq!(result_1, 5248, "Day 05 - Part 1 failed");
+        assert_eq!(result_2, 4507, "Day 05 - Part 2 failed");
+
+        let matrix = RuleMatrix::from_rules(&rules).expect("Failed to build rule matrix");
+        assert_eq!(middle_page_sum_matrix(&updates, &matrix), 5248);
+        assert_eq!(reordered_middle_page_sum_matrix(&updates, &matrix), 4507);
     }
 }
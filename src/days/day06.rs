@@ -326,8 +326,8 @@ mod tests {
         let result_1 = guard_patrol_count(&initial_pos, &map);
         let result_2 = find_all_loops_parallel(&initial_pos, &map);
 
-        assert_eq!(result_1, 5312, "Day 06 - Part 1 failed"); 
-        assert_eq!(result_2, 1748, "Day 06 - Part 2 failed");  
+        assert_eq!(result_1, 5312, "Day 06 - Part 1 failed");
+        assert_eq!(result_2, 1748, "Day 06 - Part 2 failed");
     }
     */
 }
@@ -1,20 +1,45 @@
+use num_bigint::BigUint;
 use rayon::prelude::*;
 use std::error::Error;
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
-type Calibration = Vec<(usize, Vec<usize>)>;
+type Calibration<T = usize> = Vec<(T, Vec<T>)>;
 
 pub fn run() {
     let calibration_data =
         read_input("data/day07.txt").expect("Failed to read and parse the input file");
-    let result_1 = total_calibration(&calibration_data);
-    let result_2 = total_calibration_concat(&calibration_data);
+    let result_1 = total_calibration(&calibration_data, &basic_operators())
+        .expect("Part 1 calibration total overflowed");
+    let result_2 = total_calibration(&calibration_data, &concat_operators())
+        .expect("Part 2 calibration total overflowed");
     println!("Day 07 - part 1: {}", result_1);
     println!("Day 07 - part 2: {}", result_2);
 }
 
+pub fn run_witnesses() {
+    let calibration_data =
+        read_input("data/day07.txt").expect("Failed to read and parse the input file");
+    let operators = concat_operators();
+    for (key, values) in calibration_data.iter() {
+        if let Some(witness) = find_witness(key, values, &operators) {
+            let count = count_solutions(key, values, &operators);
+            println!("{}: {} (solutions: {})", key, witness, count);
+        }
+    }
+}
+
 pub fn read_input(path: &str) -> Result<Calibration, Box<dyn Error>> {
+    read_input_as(path)
+}
+
+pub fn read_input_as<T>(path: &str) -> Result<Calibration<T>, Box<dyn Error>>
+where
+    T: CalibrationValue,
+    T::Err: fmt::Display,
+{
     let content = fs::read_to_string(Path::new(path))
         .unwrap_or_else(|_| panic!("Failed to read input file: {}", path));
 
@@ -25,22 +50,469 @@ pub fn read_input(path: &str) -> Result<Calibration, Box<dyn Error>> {
             if parts.len() != 2 {
                 return Err(format!("Invalid line {}", line));
             }
-            let key: usize = parts[0]
+            let key: T = parts[0]
                 .parse()
                 .map_err(|e| format!("Failed to parse key '{}': {}", parts[0], e))?;
-            let values: Vec<usize> = parts[1]
+            let values: Vec<T> = parts[1]
                 .split_whitespace()
                 .map(|val| {
                     val.parse()
                         .map_err(|e| format!("Failed to parse value '{}': {}", val, e))
                 })
-                .collect::<Result<Vec<usize>, String>>()?;
+                .collect::<Result<Vec<T>, String>>()?;
             Ok((key, values))
         })
-        .collect::<Result<Vec<(usize, Vec<usize>)>, String>>()?;
+        .collect::<Result<Calibration<T>, String>>()?;
     Ok(calibration_data)
 }
 
+pub trait CalibrationValue:
+    Clone + PartialOrd + FromStr + fmt::Display + Send + Sync + 'static
+{
+    fn zero() -> Self;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    fn checked_div_exact(&self, rhs: &Self) -> Option<Self>;
+    fn is_zero(&self) -> bool;
+    fn decimal_shift(&self) -> Option<Self>;
+    fn xor(&self, rhs: &Self) -> Self;
+}
+
+macro_rules! impl_calibration_value {
+    ($($t:ty),*) => {
+        $(
+            impl CalibrationValue for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *rhs)
+                }
+
+                fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                    <$t>::checked_sub(*self, *rhs)
+                }
+
+                fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                    <$t>::checked_mul(*self, *rhs)
+                }
+
+                fn checked_div_exact(&self, rhs: &Self) -> Option<Self> {
+                    if *rhs != 0 && self.is_multiple_of(*rhs) {
+                        Some(self / rhs)
+                    } else {
+                        None
+                    }
+                }
+
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                fn decimal_shift(&self) -> Option<Self> {
+                    let mut power: $t = 10;
+                    while power <= *self {
+                        power = power.checked_mul(10)?;
+                    }
+                    Some(power)
+                }
+
+                fn xor(&self, rhs: &Self) -> Self {
+                    self ^ rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_calibration_value!(u8, u16, u32, u64, u128, usize);
+
+impl CalibrationValue for BigUint {
+    fn zero() -> Self {
+        BigUint::from(0u32)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        if self >= rhs {
+            Some(self - rhs)
+        } else {
+            None
+        }
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_div_exact(&self, rhs: &Self) -> Option<Self> {
+        if !rhs.is_zero() && (self % rhs).is_zero() {
+            Some(self / rhs)
+        } else {
+            None
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.bits() == 0
+    }
+
+    fn decimal_shift(&self) -> Option<Self> {
+        let mut power = BigUint::from(10u32);
+        while power <= *self {
+            power *= 10u32;
+        }
+        Some(power)
+    }
+
+    fn xor(&self, rhs: &Self) -> Self {
+        self ^ rhs
+    }
+}
+
+pub enum Inverse<T> {
+    Operand(T),
+    Unreachable,
+    Unsupported,
+}
+
+pub trait Operator<T>: Send + Sync {
+    fn symbol(&self) -> &str;
+
+    fn apply(&self, lhs: &T, rhs: &T) -> Option<T>;
+
+    // Returns the unique left operand producing `target`, or `Unsupported` when the
+    // operator has no inverse or it is ambiguous, in which case the solver searches forwards.
+    fn invert(&self, _target: &T, _rhs: &T) -> Inverse<T> {
+        Inverse::Unsupported
+    }
+
+    fn is_non_decreasing(&self, _rhs: &T) -> bool {
+        false
+    }
+}
+
+pub struct Add;
+pub struct Multiply;
+pub struct Concat;
+pub struct Subtract;
+pub struct Xor;
+
+pub fn basic_operators<T: CalibrationValue>() -> Vec<&'static dyn Operator<T>> {
+    vec![&Add, &Multiply]
+}
+
+pub fn concat_operators<T: CalibrationValue>() -> Vec<&'static dyn Operator<T>> {
+    vec![&Add, &Multiply, &Concat]
+}
+
+impl<T: CalibrationValue> Operator<T> for Add {
+    fn symbol(&self) -> &str {
+        "+"
+    }
+
+    fn apply(&self, lhs: &T, rhs: &T) -> Option<T> {
+        lhs.checked_add(rhs)
+    }
+
+    fn invert(&self, target: &T, rhs: &T) -> Inverse<T> {
+        target
+            .checked_sub(rhs)
+            .map_or(Inverse::Unreachable, Inverse::Operand)
+    }
+
+    fn is_non_decreasing(&self, _rhs: &T) -> bool {
+        true
+    }
+}
+
+impl<T: CalibrationValue> Operator<T> for Multiply {
+    fn symbol(&self) -> &str {
+        "*"
+    }
+
+    fn apply(&self, lhs: &T, rhs: &T) -> Option<T> {
+        lhs.checked_mul(rhs)
+    }
+
+    fn invert(&self, target: &T, rhs: &T) -> Inverse<T> {
+        if rhs.is_zero() {
+            Inverse::Unsupported
+        } else {
+            target
+                .checked_div_exact(rhs)
+                .map_or(Inverse::Unreachable, Inverse::Operand)
+        }
+    }
+
+    fn is_non_decreasing(&self, rhs: &T) -> bool {
+        !rhs.is_zero()
+    }
+}
+
+impl<T: CalibrationValue> Operator<T> for Concat {
+    fn symbol(&self) -> &str {
+        "||"
+    }
+
+    fn apply(&self, lhs: &T, rhs: &T) -> Option<T> {
+        lhs.checked_mul(&rhs.decimal_shift()?)?.checked_add(rhs)
+    }
+
+    fn invert(&self, target: &T, rhs: &T) -> Inverse<T> {
+        target
+            .checked_sub(rhs)
+            .zip(rhs.decimal_shift())
+            .and_then(|(prefix, power)| prefix.checked_div_exact(&power))
+            .map_or(Inverse::Unreachable, Inverse::Operand)
+    }
+
+    fn is_non_decreasing(&self, _rhs: &T) -> bool {
+        true
+    }
+}
+
+impl<T: CalibrationValue> Operator<T> for Subtract {
+    fn symbol(&self) -> &str {
+        "-"
+    }
+
+    fn apply(&self, lhs: &T, rhs: &T) -> Option<T> {
+        lhs.checked_sub(rhs)
+    }
+
+    fn invert(&self, target: &T, rhs: &T) -> Inverse<T> {
+        target
+            .checked_add(rhs)
+            .map_or(Inverse::Unreachable, Inverse::Operand)
+    }
+
+    fn is_non_decreasing(&self, rhs: &T) -> bool {
+        rhs.is_zero()
+    }
+}
+
+impl<T: CalibrationValue> Operator<T> for Xor {
+    fn symbol(&self) -> &str {
+        "^"
+    }
+
+    fn apply(&self, lhs: &T, rhs: &T) -> Option<T> {
+        Some(lhs.xor(rhs))
+    }
+
+    fn invert(&self, target: &T, rhs: &T) -> Inverse<T> {
+        Inverse::Operand(target.xor(rhs))
+    }
+}
+
+pub struct Witness<'a, T> {
+    values: &'a [T],
+    operators: Vec<&'a dyn Operator<T>>,
+}
+
+impl<T> Witness<'_, T> {
+    pub fn symbols(&self) -> Vec<&str> {
+        self.operators
+            .iter()
+            .map(|operator| operator.symbol())
+            .collect()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Witness<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.values[0])?;
+        for (operator, value) in self.operators.iter().zip(&self.values[1..]) {
+            write!(f, " {} {}", operator.symbol(), value)?;
+        }
+        Ok(())
+    }
+}
+
+// Accepts a forward search's final accumulator; with `bound`, partial results above it are
+// pruned (only valid when every operator is non-decreasing).
+struct Goal<'g, T> {
+    bound: Option<&'g T>,
+    accept: &'g dyn Fn(&T) -> bool,
+}
+
+// `assignment[slot]` holds the index of the operator applied between values `slot` and
+// `slot + 1`; `visit` is called on every complete assignment and returns true to stop.
+struct Search<'s, 'o, T> {
+    operators: &'s [&'o dyn Operator<T>],
+    assignment: Vec<usize>,
+    visit: &'s mut dyn FnMut(&[usize]) -> bool,
+}
+
+impl<'s, 'o, T: CalibrationValue> Search<'s, 'o, T> {
+    fn new(
+        values: &[T],
+        operators: &'s [&'o dyn Operator<T>],
+        visit: &'s mut dyn FnMut(&[usize]) -> bool,
+    ) -> Self {
+        Search {
+            operators,
+            assignment: vec![0; values.len().saturating_sub(1)],
+            visit,
+        }
+    }
+
+    fn forward(&mut self, accumulator: T, values: &[T], slot: usize, goal: &Goal<T>) -> bool {
+        if goal.bound.is_some_and(|bound| accumulator > *bound) {
+            return false;
+        }
+        let (value, rest) = match values.split_first() {
+            Some(split) => split,
+            None => return (goal.accept)(&accumulator) && (self.visit)(&self.assignment),
+        };
+        for (idx, operator) in self.operators.iter().enumerate() {
+            if let Some(result) = operator.apply(&accumulator, value) {
+                self.assignment[slot] = idx;
+                if self.forward(result, rest, slot + 1, goal) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn backwards(&mut self, target: &T, values: &[T]) -> bool {
+        let (last, rest) = match values.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+        let (first, middle) = match rest.split_first() {
+            Some(split) => split,
+            None => return target == last && (self.visit)(&self.assignment),
+        };
+        let slot = rest.len() - 1;
+        let operators = self.operators;
+        for (idx, operator) in operators.iter().enumerate() {
+            self.assignment[slot] = idx;
+            let found = match operator.invert(target, last) {
+                Inverse::Operand(lhs) => self.backwards(&lhs, rest),
+                Inverse::Unreachable => false,
+                Inverse::Unsupported => {
+                    let accept = |lhs: &T| operator.apply(lhs, last).as_ref() == Some(target);
+                    let goal = Goal {
+                        bound: None,
+                        accept: &accept,
+                    };
+                    self.forward(first.clone(), middle, 0, &goal)
+                }
+            };
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn solve_forward<T: CalibrationValue>(
+    key: &T,
+    values: &[T],
+    operators: &[&dyn Operator<T>],
+) -> bool {
+    let (first, rest) = match values.split_first() {
+        Some(split) => split,
+        None => return false,
+    };
+    let prunable = rest.iter().all(|value| {
+        operators
+            .iter()
+            .all(|operator| operator.is_non_decreasing(value))
+    });
+    let goal = Goal {
+        bound: if prunable { Some(key) } else { None },
+        accept: &|result| result == key,
+    };
+    Search::new(values, operators, &mut |_| true).forward(first.clone(), rest, 0, &goal)
+}
+
+fn solve_backwards<T: CalibrationValue>(
+    target: &T,
+    values: &[T],
+    operators: &[&dyn Operator<T>],
+) -> bool {
+    Search::new(values, operators, &mut |_| true).backwards(target, values)
+}
+
+pub fn is_solvable<T: CalibrationValue>(
+    key: &T,
+    values: &[T],
+    operators: &[&dyn Operator<T>],
+) -> bool {
+    solve_backwards(key, values, operators)
+}
+
+pub fn find_witness<'a, T: CalibrationValue>(
+    key: &T,
+    values: &'a [T],
+    operators: &[&'a dyn Operator<T>],
+) -> Option<Witness<'a, T>> {
+    let mut witness = None;
+    let mut record = |found: &[usize]| {
+        witness = Some(found.iter().map(|&idx| operators[idx]).collect());
+        true
+    };
+    Search::new(values, operators, &mut record).backwards(key, values);
+    witness.map(|operators| Witness { values, operators })
+}
+
+pub fn count_solutions<T: CalibrationValue>(
+    key: &T,
+    values: &[T],
+    operators: &[&dyn Operator<T>],
+) -> usize {
+    let mut count = 0;
+    let mut tally = |_: &[usize]| {
+        count += 1;
+        false
+    };
+    Search::new(values, operators, &mut tally).backwards(key, values);
+    count
+}
+
+fn sum_keys<T: CalibrationValue>(
+    calibration_data: &[(T, Vec<T>)],
+    solvable: impl Fn(&T, &[T]) -> bool + Sync,
+) -> Option<T> {
+    calibration_data
+        .par_iter()
+        .filter(|(key, values)| solvable(key, values))
+        .map(|(key, _)| Some(key.clone()))
+        .reduce(
+            || Some(T::zero()),
+            |a, b| a.zip(b).and_then(|(a, b)| a.checked_add(&b)),
+        )
+}
+
+pub fn total_calibration<T: CalibrationValue>(
+    calibration_data: &[(T, Vec<T>)],
+    operators: &[&dyn Operator<T>],
+) -> Option<T> {
+    sum_keys(calibration_data, |key, values| {
+        solve_backwards(key, values, operators)
+    })
+}
+
+pub fn total_calibration_forward<T: CalibrationValue>(
+    calibration_data: &[(T, Vec<T>)],
+    operators: &[&dyn Operator<T>],
+) -> Option<T> {
+    sum_keys(calibration_data, |key, values| {
+        solve_forward(key, values, operators)
+    })
+}
+
+// The original forward search, which expands every combination, kept as the benchmark baseline.
 fn operator_calibration(key: &usize, values: &[usize]) -> bool {
     let mut results: Vec<usize> = vec![values[0]];
     for &value in &values[1..] {
@@ -81,7 +553,7 @@ fn operator_calibration_concat(key: &usize, values: &[usize]) -> bool {
     results.contains(key)
 }
 
-pub fn total_calibration(calibration_data: &[(usize, Vec<usize>)]) -> usize {
+pub fn total_calibration_expanded(calibration_data: &[(usize, Vec<usize>)]) -> usize {
     calibration_data
         .par_iter()
         .filter(|(key, values)| operator_calibration(key, values))
@@ -89,7 +561,7 @@ pub fn total_calibration(calibration_data: &[(usize, Vec<usize>)]) -> usize {
         .sum()
 }
 
-pub fn total_calibration_concat(calibration_data: &[(usize, Vec<usize>)]) -> usize {
+pub fn total_calibration_concat_expanded(calibration_data: &[(usize, Vec<usize>)]) -> usize {
     calibration_data
         .par_iter()
         .filter(|(key, values)| operator_calibration_concat(key, values))
@@ -101,83 +573,247 @@ pub fn total_calibration_concat(calibration_data: &[(usize, Vec<usize>)]) -> usi
 mod tests {
     use super::*;
 
+    fn example_cases() -> Vec<(usize, Vec<usize>)> {
+        vec![
+            (190, vec![10, 19]),
+            (3267, vec![81, 40, 27]),
+            (83, vec![17, 5]),
+            (156, vec![15, 6]),
+            (7290, vec![6, 8, 6, 15]),
+            (161011, vec![16, 10, 13]),
+            (192, vec![17, 8, 14]),
+            (21037, vec![9, 7, 18, 13]),
+            (292, vec![11, 6, 16, 20]),
+        ]
+    }
+
     #[test]
     fn test_operator_calibration() {
-        let test_cases = vec![
-            (190, vec![10, 19], true),
-            (3267, vec![81, 40, 27], true),
-            (83, vec![17, 5], false),
-            (156, vec![15, 6], false),
-            (7290, vec![6, 8, 6, 15], false),
-            (161011, vec![16, 10, 13], false),
-            (192, vec![17, 8, 14], false),
-            (21037, vec![9, 7, 18, 13], false),
-            (292, vec![11, 6, 16, 20], true),
-        ];
+        let expected = vec![true, true, false, false, false, false, false, false, true];
 
-        for (key, values, expected) in test_cases {
-            let result = operator_calibration(&key, &values);
+        for ((key, values), expected) in example_cases().into_iter().zip(expected) {
+            let result = solve_forward(&key, &values, &basic_operators());
+            let result_backwards = solve_backwards(&key, &values, &basic_operators());
             assert_eq!(
-                result, expected,
-                "Failed operator calibration for key: {}, values: {:?}. Expected {}, got {}",
-                key, values, expected, result
+                (result, result_backwards),
+                (expected, expected),
+                "Failed operator calibration for key: {}, values: {:?}. Expected {}",
+                key,
+                values,
+                expected
             );
         }
     }
 
     #[test]
     fn test_operator_calibration_concat() {
+        let expected = vec![true, true, false, true, true, false, true, false, true];
+
+        for ((key, values), expected) in example_cases().into_iter().zip(expected) {
+            let result = solve_forward(&key, &values, &concat_operators());
+            let result_backwards = solve_backwards(&key, &values, &concat_operators());
+            assert_eq!(
+                (result, result_backwards),
+                (expected, expected),
+                "Failed operator calibration for key: {}, values: {:?}. Expected {}",
+                key,
+                values,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_operands() {
+        let test_cases: Vec<(usize, Vec<usize>, _, bool)> = vec![
+            (0, vec![5, 0], basic_operators(), true),
+            (50, vec![5, 0], basic_operators(), false),
+            (50, vec![5, 0], concat_operators(), true),
+            (0, vec![3, 4, 0], basic_operators(), true),
+            (7, vec![3, 4, 0], basic_operators(), true),
+        ];
+
+        for (key, values, operators, expected) in test_cases {
+            let result = solve_forward(&key, &values, &operators);
+            let result_backwards = solve_backwards(&key, &values, &operators);
+            assert_eq!(
+                (result, result_backwards),
+                (expected, expected),
+                "Failed zero operand calibration for key: {}, values: {:?}",
+                key,
+                values
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_operators() {
+        let subtract: &[&dyn Operator<usize>] = &[&Add, &Subtract];
+        let xor: &[&dyn Operator<usize>] = &[&Xor, &Multiply];
+
         let test_cases = vec![
-            (190, vec![10, 19], true),
-            (3267, vec![81, 40, 27], true),
-            (83, vec![17, 5], false),
-            (156, vec![15, 6], true),
-            (7290, vec![6, 8, 6, 15], true),
-            (161011, vec![16, 10, 13], false),
-            (192, vec![17, 8, 14], true),
-            (21037, vec![9, 7, 18, 13], false),
-            (292, vec![11, 6, 16, 20], true),
+            (4, vec![10, 3, 3], subtract, true),
+            (9, vec![3, 10, 3], subtract, false),
+            (12, vec![5, 3, 2], xor, true),
+            (6, vec![5, 3, 2], xor, false),
+        ];
+
+        for (key, values, operators, expected) in test_cases {
+            let result = solve_forward(&key, &values, operators);
+            let result_backwards = solve_backwards(&key, &values, operators);
+            assert_eq!(
+                (result, result_backwards),
+                (expected, expected),
+                "Failed custom operator calibration for key: {}, values: {:?}",
+                key,
+                values
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_witness() {
+        let values: Vec<usize> = vec![81, 40, 27];
+        let witness = find_witness(&3267, &values, &basic_operators()).expect("No witness found");
+        assert_eq!(witness.symbols().len(), 2);
+        assert!(
+            ["81 + 40 * 27", "81 * 40 + 27"].contains(&witness.to_string().as_str()),
+            "Unexpected witness {}",
+            witness
+        );
+
+        let values: Vec<usize> = vec![6, 8, 6, 15];
+        let witness = find_witness(&7290, &values, &concat_operators()).expect("No witness found");
+        assert_eq!(witness.to_string(), "6 * 8 || 6 * 15");
+
+        let values: Vec<usize> = vec![17, 5];
+        assert!(find_witness(&83, &values, &concat_operators()).is_none());
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        let test_cases: Vec<(usize, Vec<usize>, _, usize)> = vec![
+            (3267, vec![81, 40, 27], basic_operators(), 2),
+            (292, vec![11, 6, 16, 20], basic_operators(), 1),
+            (83, vec![17, 5], basic_operators(), 0),
+            (4, vec![2, 2], basic_operators(), 2),
+            (0, vec![3, 4, 0], basic_operators(), 2),
         ];
 
-        for (key, values, expected) in test_cases {
-            let result = operator_calibration_concat(&key, &values);
+        for (key, values, operators, expected) in test_cases {
+            let result = count_solutions(&key, &values, &operators);
             assert_eq!(
                 result, expected,
-                "Failed operator calibration for key: {}, values: {:?}. Expected {}, got {}",
+                "Failed solution count for key: {}, values: {:?}. Expected {}, got {}",
                 key, values, expected, result
             );
         }
     }
+
     #[test]
     fn test_total_calibration() {
-        let mut input = Vec::new();
-        input.push((190, vec![10, 19]));
-        input.push((3267, vec![81, 40, 27]));
-        input.push((83, vec![17, 5]));
-        input.push((156, vec![15, 6]));
-        input.push((7290, vec![6, 8, 6, 15]));
-        input.push((161011, vec![16, 10, 13]));
-        input.push((192, vec![17, 8, 14]));
-        input.push((21037, vec![9, 7, 18, 13]));
-        input.push((292, vec![11, 6, 16, 20]));
-
-        let expected_sum = 3749;
-        let result = total_calibration(&input);
+        let input = example_cases();
+
+        let expected_sum = Some(3749);
+        let result = total_calibration(&input, &basic_operators());
+        assert_eq!(
+            result, expected_sum,
+            "Failed sum_valid_keys, expected sum: {:?}, got {:?}",
+            expected_sum, result
+        );
+
+        let expected_sum = Some(11387);
+        let result = total_calibration(&input, &concat_operators());
         assert_eq!(
             result, expected_sum,
-            "Failed sum_valid_keys, expected sum: {}, got {}",
+            "Failed sum_valid_keys with concatenation, expected sum: {:?}, got {:?}",
             expected_sum, result
         );
+
+        assert_eq!(total_calibration_expanded(&input), 3749);
+        assert_eq!(total_calibration_concat_expanded(&input), 11387);
+    }
+
+    #[test]
+    fn test_checked_overflow() {
+        // 16 * 16 + 4 wraps to 4 in u8, which must not count as a solution.
+        let values: Vec<u8> = vec![16, 16, 4];
+        assert!(!solve_forward(&4, &values, &basic_operators()));
+        assert!(!solve_backwards(&4, &values, &basic_operators()));
+        assert_eq!(count_solutions(&4, &values, &basic_operators()), 0);
+
+        assert_eq!(Operator::<u8>::apply(&Concat, &25, &5), Some(255));
+        assert_eq!(Operator::<u8>::apply(&Concat, &26, &5), None);
+        assert_eq!(Operator::<u8>::apply(&Concat, &1, &100), None);
+
+        let input: Vec<(u8, Vec<u8>)> = vec![(200, vec![100, 100]), (100, vec![50, 50])];
+        assert_eq!(total_calibration(&input, &basic_operators()), None);
+    }
+
+    #[test]
+    fn test_wide_calibration_values() {
+        let key: u128 = 1_000_000_000_000_000_000_000_000_000;
+        let values: Vec<u128> = vec![1_000_000_000_000_000, 1_000_000_000_000];
+        assert!(solve_backwards(&key, &values, &basic_operators()));
+        assert!(solve_forward(&key, &values, &basic_operators()));
+
+        let key: BigUint = "123456789012345678901234567890123456789012345"
+            .parse()
+            .unwrap();
+        let values: Vec<BigUint> = ["123456789012345678901234567890", "123456789012345"]
+            .iter()
+            .map(|value| value.parse().unwrap())
+            .collect();
+        assert!(solve_backwards(&key, &values, &concat_operators()));
+        assert!(solve_forward(&key, &values, &concat_operators()));
+        assert!(!solve_backwards(&key, &values, &basic_operators()));
+        let witness = find_witness(&key, &values, &concat_operators()).expect("No witness found");
+        assert_eq!(witness.symbols(), vec!["||"]);
     }
 
     #[test]
     fn test_day07_part1_and_part2() {
-        let calibration_data =
-            read_input("data/day07.txt").expect("Failed to read test input");
-        let result_1 = total_calibration(&calibration_data);
-        let result_2 = total_calibration_concat(&calibration_data);
+        let calibration_data = read_input("data/day07.txt").expect("Failed to read test input");
+        let result_1 = total_calibration(&calibration_data, &basic_operators());
+        let result_2 = total_calibration(&calibration_data, &concat_operators());
 
-        assert_eq!(result_1, 1399219271639, "Day 07 - Part 1 failed"); 
-        assert_eq!(result_2, 275791737999003, "Day 07 - Part 2 failed"); 
+        assert_eq!(result_1, Some(1399219271639), "Day 07 - Part 1 failed");
+        assert_eq!(result_2, Some(275791737999003), "Day 07 - Part 2 failed");
+
+        let result_1 = total_calibration_forward(&calibration_data, &basic_operators());
+        let result_2 = total_calibration_forward(&calibration_data, &concat_operators());
+        assert_eq!(
+            result_1,
+            Some(1399219271639),
+            "Day 07 - Part 1 forward failed"
+        );
+        assert_eq!(
+            result_2,
+            Some(275791737999003),
+            "Day 07 - Part 2 forward failed"
+        );
+        assert_eq!(
+            total_calibration_concat_expanded(&calibration_data),
+            275791737999003,
+            "Day 07 - Part 2 expanded failed"
+        );
+
+        let calibration_data: Calibration<u64> =
+            read_input_as("data/day07.txt").expect("Failed to read test input");
+        let result_2 = total_calibration(&calibration_data, &concat_operators());
+        assert_eq!(
+            result_2,
+            Some(275791737999003),
+            "Day 07 - Part 2 u64 failed"
+        );
+
+        let calibration_data: Calibration<BigUint> =
+            read_input_as("data/day07.txt").expect("Failed to read test input");
+        let result_2 = total_calibration(&calibration_data, &concat_operators());
+        assert_eq!(
+            result_2,
+            Some(BigUint::from(275791737999003u64)),
+            "Day 07 - Part 2 BigUint failed"
+        );
     }
 }
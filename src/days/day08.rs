@@ -1,11 +1,13 @@
+use crate::combinatorics::{pairs, par_pairs};
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
 type Position = (usize, usize);
 type FrequencyMap = HashMap<char, Vec<Position>>;
 type Boundaries = (usize, usize);
+type AntennaPair = (Position, Position);
 
 pub fn run() {
     let (frequency_map, map_boundaries) =
@@ -16,6 +18,38 @@ pub fn run() {
     println!("Day 08 - part 2: {}", result_2);
 }
 
+pub fn run_report() {
+    let (frequency_map, map_boundaries) =
+        read_input("data/day08.txt").expect("Failed to read and parse the input file");
+    for (part, config) in [
+        (1, ResonanceConfig::part_1()),
+        (2, ResonanceConfig::part_2()),
+    ] {
+        let report = antinode_report(&frequency_map, &map_boundaries, &config);
+        println!("Day 08 - part {}: {}", part, report.total());
+        println!(
+            "{:>9} {:>8} {:>5} {:>9} {:>6}",
+            "frequency", "antennas", "pairs", "antinodes", "shared"
+        );
+        for (&frequency, antinodes) in report.by_frequency.iter() {
+            let antennas = frequency_map[&frequency].len();
+            let shared = antinodes
+                .keys()
+                .filter(|position| report.frequencies_at(position).len() > 1)
+                .count();
+            println!(
+                "{:>9} {:>8} {:>5} {:>9} {:>6}",
+                frequency,
+                antennas,
+                antennas * antennas.saturating_sub(1) / 2,
+                antinodes.len(),
+                shared
+            );
+        }
+        println!("{}", render_map(&frequency_map, &map_boundaries, &report));
+    }
+}
+
 pub fn read_input(path: &str) -> Result<(FrequencyMap, Boundaries), String> {
     let content = fs::read_to_string(Path::new(path))
         .unwrap_or_else(|_| panic!("Failed to read input file: {}", path));
@@ -50,105 +84,362 @@ fn is_in_boundaries(position: &Position, boundaries: &Boundaries) -> bool {
     position.0 < boundaries.0 && position.1 < boundaries.1
 }
 
-fn find_antinodes(
+pub fn find_all_antinodes(frequency_map: &FrequencyMap, boundaries: &Boundaries) -> usize {
+    find_all_antinodes_with(frequency_map, boundaries, &ResonanceConfig::part_1())
+}
+
+pub fn find_all_antinodes_resonant(frequency_map: &FrequencyMap, boundaries: &Boundaries) -> usize {
+    find_all_antinodes_with(frequency_map, boundaries, &ResonanceConfig::part_2())
+}
+
+pub enum Harmonics {
+    Unbounded,
+    Ratios(Vec<usize>),
+}
+
+pub struct ResonanceConfig {
+    pub reduce_step: bool,
+    pub harmonics: Harmonics,
+}
+
+impl ResonanceConfig {
+    pub fn part_1() -> Self {
+        ResonanceConfig {
+            reduce_step: false,
+            harmonics: Harmonics::Ratios(vec![1]),
+        }
+    }
+
+    pub fn part_2() -> Self {
+        ResonanceConfig {
+            reduce_step: false,
+            harmonics: Harmonics::Unbounded,
+        }
+    }
+
+    pub fn lattice() -> Self {
+        ResonanceConfig {
+            reduce_step: true,
+            harmonics: Harmonics::Unbounded,
+        }
+    }
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn to_position(x: isize, y: isize, boundaries: &Boundaries) -> Option<Position> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    let position = (x as usize, y as usize);
+    if is_in_boundaries(&position, boundaries) {
+        Some(position)
+    } else {
+        None
+    }
+}
+
+fn visit_antinodes(
     position_1: &Position,
     position_2: &Position,
     boundaries: &Boundaries,
-) -> HashSet<Position> {
+    config: &ResonanceConfig,
+    visit: &mut impl FnMut(Position),
+) {
     let (x1, y1) = (position_1.0 as isize, position_1.1 as isize);
     let (x2, y2) = (position_2.0 as isize, position_2.1 as isize);
 
-    let dx = x2 - x1;
-    let dy = y2 - y1;
+    let (mut dx, mut dy) = (x2 - x1, y2 - y1);
+    if config.reduce_step {
+        let divisor = gcd(dx, dy).max(1);
+        dx /= divisor;
+        dy /= divisor;
+    }
 
-    let antinode_right = ((x2 + dx) as usize, (y2 + dy) as usize);
-    let antinode_left = ((x1 - dx) as usize, (y1 - dy) as usize);
+    match &config.harmonics {
+        Harmonics::Unbounded => {
+            for direction in [1, -1] {
+                let (mut curr_x, mut curr_y) = (x1, y1);
+                while let Some(position) = to_position(curr_x, curr_y, boundaries) {
+                    visit(position);
+                    curr_x += direction * dx;
+                    curr_y += direction * dy;
+                }
+            }
+        }
+        Harmonics::Ratios(ratios) => {
+            for &ratio in ratios {
+                let k = ratio as isize;
+                if let Some(position) = to_position(x2 + k * dx, y2 + k * dy, boundaries) {
+                    visit(position);
+                }
+                if let Some(position) = to_position(x1 - k * dx, y1 - k * dy, boundaries) {
+                    visit(position);
+                }
+            }
+        }
+    }
+}
 
+fn find_antinodes_with(
+    position_1: &Position,
+    position_2: &Position,
+    boundaries: &Boundaries,
+    config: &ResonanceConfig,
+) -> HashSet<Position> {
     let mut antinodes = HashSet::new();
-    if is_in_boundaries(&antinode_right, boundaries) {
-        antinodes.insert(antinode_right);
-    }
-    if is_in_boundaries(&antinode_left, boundaries) {
-        antinodes.insert(antinode_left);
-    }
+    visit_antinodes(
+        position_1,
+        position_2,
+        boundaries,
+        config,
+        &mut |position| {
+            antinodes.insert(position);
+        },
+    );
     antinodes
 }
 
-fn generate_combinations(positions: &[Position]) -> Vec<(&Position, &Position)> {
-    let mut pairs = Vec::new();
-    for i in 0..positions.len() {
-        for j in (i + 1)..positions.len() {
-            pairs.push((&positions[i], &positions[j]));
+struct AntinodeGrid {
+    cols: usize,
+    words: Vec<u64>,
+}
+
+impl AntinodeGrid {
+    fn new(boundaries: &Boundaries) -> Self {
+        AntinodeGrid {
+            cols: boundaries.1,
+            words: vec![0; (boundaries.0 * boundaries.1).div_ceil(64)],
         }
     }
-    pairs
+
+    fn insert(&mut self, position: Position) {
+        let idx = position.0 * self.cols + position.1;
+        self.words[idx / 64] |= 1 << (idx % 64);
+    }
+
+    fn union(mut self, other: Self) -> Self {
+        for (word, other_word) in self.words.iter_mut().zip(other.words) {
+            *word |= other_word;
+        }
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
 }
 
-pub fn find_all_antinodes(frequency_map: &FrequencyMap, boundaries: &Boundaries) -> usize {
+pub fn find_all_antinodes_with(
+    frequency_map: &FrequencyMap,
+    boundaries: &Boundaries,
+    config: &ResonanceConfig,
+) -> usize {
     frequency_map
         .par_iter()
-        .flat_map(|(_key, positions)| {
-            let pairs = generate_combinations(positions);
-            pairs
-                .par_iter()
-                .flat_map(|(p1, p2)| find_antinodes(p1, p2, boundaries))
-                .collect::<HashSet<Position>>()
-        })
-        .collect::<HashSet<Position>>()
+        .flat_map(|(_key, positions)| par_pairs(positions))
+        .fold(
+            || AntinodeGrid::new(boundaries),
+            |mut grid, (p1, p2)| {
+                visit_antinodes(p1, p2, boundaries, config, &mut |position| {
+                    grid.insert(position)
+                });
+                grid
+            },
+        )
+        .reduce(|| AntinodeGrid::new(boundaries), AntinodeGrid::union)
         .len()
 }
 
-fn find_antinodes_resonant(
-    position_1: &Position,
-    position_2: &Position,
-    boundaries: &Boundaries,
-) -> HashSet<Position> {
-    let (x1, y1) = (position_1.0 as isize, position_1.1 as isize);
-    let (x2, y2) = (position_2.0 as isize, position_2.1 as isize);
+pub struct AntinodeReport {
+    pub by_frequency: BTreeMap<char, BTreeMap<Position, Vec<AntennaPair>>>,
+}
 
-    let dx = x2 - x1;
-    let dy = y2 - y1;
+impl AntinodeReport {
+    pub fn positions(&self, frequency: char) -> Vec<Position> {
+        self.by_frequency
+            .get(&frequency)
+            .map_or_else(Vec::new, |antinodes| antinodes.keys().cloned().collect())
+    }
 
-    let mut antinodes = HashSet::new();
-    antinodes.insert(*position_1);
-    antinodes.insert(*position_2);
+    pub fn sources(&self, position: &Position) -> Vec<(char, AntennaPair)> {
+        self.by_frequency
+            .iter()
+            .filter_map(|(&frequency, antinodes)| {
+                antinodes.get(position).map(|pairs| (frequency, pairs))
+            })
+            .flat_map(|(frequency, pairs)| pairs.iter().map(move |&pair| (frequency, pair)))
+            .collect()
+    }
+
+    pub fn frequencies_at(&self, position: &Position) -> Vec<char> {
+        self.by_frequency
+            .iter()
+            .filter(|(_, antinodes)| antinodes.contains_key(position))
+            .map(|(&frequency, _)| frequency)
+            .collect()
+    }
 
-    let mut curr_x = x2 + dx;
-    let mut curr_y = y2 + dy;
-    while is_in_boundaries(&(curr_x as usize, curr_y as usize), boundaries) {
-        antinodes.insert((curr_x as usize, curr_y as usize));
-        curr_x += dx;
-        curr_y += dy;
+    pub fn overlaps(&self) -> BTreeMap<Position, Vec<char>> {
+        let mut frequencies: BTreeMap<Position, Vec<char>> = BTreeMap::new();
+        for (&frequency, antinodes) in self.by_frequency.iter() {
+            for &position in antinodes.keys() {
+                frequencies.entry(position).or_default().push(frequency);
+            }
+        }
+        frequencies.retain(|_, frequencies| frequencies.len() > 1);
+        frequencies
     }
 
-    let mut curr_x = x1 - dx;
-    let mut curr_y = y1 - dy;
-    while is_in_boundaries(&(curr_x as usize, curr_y as usize), boundaries) {
-        antinodes.insert((curr_x as usize, curr_y as usize));
-        curr_x -= dx;
-        curr_y -= dy;
+    pub fn total(&self) -> usize {
+        self.by_frequency
+            .values()
+            .flat_map(|antinodes| antinodes.keys())
+            .collect::<BTreeSet<&Position>>()
+            .len()
     }
-    antinodes
 }
 
-pub fn find_all_antinodes_resonant(frequency_map: &FrequencyMap, boundaries: &Boundaries) -> usize {
-    frequency_map
-        .par_iter()
-        .flat_map(|(_key, positions)| {
-            let pairs = generate_combinations(positions);
-            pairs
-                .par_iter()
-                .flat_map(|(p1, p2)| find_antinodes_resonant(p1, p2, boundaries))
-                .collect::<HashSet<Position>>()
+pub fn antinode_report(
+    frequency_map: &FrequencyMap,
+    boundaries: &Boundaries,
+    config: &ResonanceConfig,
+) -> AntinodeReport {
+    let by_frequency = frequency_map
+        .iter()
+        .map(|(&frequency, positions)| {
+            let mut antinodes: BTreeMap<Position, Vec<AntennaPair>> = BTreeMap::new();
+            for (p1, p2) in pairs(positions) {
+                for antinode in find_antinodes_with(p1, p2, boundaries, config) {
+                    antinodes.entry(antinode).or_default().push((*p1, *p2));
+                }
+            }
+            for pairs in antinodes.values_mut() {
+                pairs.sort_unstable();
+            }
+            (frequency, antinodes)
         })
-        .collect::<HashSet<Position>>()
-        .len()
+        .collect();
+    AntinodeReport { by_frequency }
+}
+
+pub fn render_map(
+    frequency_map: &FrequencyMap,
+    boundaries: &Boundaries,
+    report: &AntinodeReport,
+) -> String {
+    let mut grid = vec![vec!['.'; boundaries.1]; boundaries.0];
+    for antinodes in report.by_frequency.values() {
+        for &(row, col) in antinodes.keys() {
+            grid[row][col] = '#';
+        }
+    }
+    for (&frequency, positions) in frequency_map.iter() {
+        for &(row, col) in positions {
+            grid[row][col] = frequency;
+        }
+    }
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Reference implementations from before the configurable antinode search; the presets
+    // must keep matching them.
+    fn find_antinodes(
+        position_1: &Position,
+        position_2: &Position,
+        boundaries: &Boundaries,
+    ) -> HashSet<Position> {
+        let (x1, y1) = (position_1.0 as isize, position_1.1 as isize);
+        let (x2, y2) = (position_2.0 as isize, position_2.1 as isize);
+
+        let dx = x2 - x1;
+        let dy = y2 - y1;
+
+        let antinode_right = ((x2 + dx) as usize, (y2 + dy) as usize);
+        let antinode_left = ((x1 - dx) as usize, (y1 - dy) as usize);
+
+        let mut antinodes = HashSet::new();
+        if is_in_boundaries(&antinode_right, boundaries) {
+            antinodes.insert(antinode_right);
+        }
+        if is_in_boundaries(&antinode_left, boundaries) {
+            antinodes.insert(antinode_left);
+        }
+        antinodes
+    }
+
+    fn find_antinodes_resonant(
+        position_1: &Position,
+        position_2: &Position,
+        boundaries: &Boundaries,
+    ) -> HashSet<Position> {
+        let (x1, y1) = (position_1.0 as isize, position_1.1 as isize);
+        let (x2, y2) = (position_2.0 as isize, position_2.1 as isize);
+
+        let dx = x2 - x1;
+        let dy = y2 - y1;
+
+        let mut antinodes = HashSet::new();
+        antinodes.insert(*position_1);
+        antinodes.insert(*position_2);
+
+        let mut curr_x = x2 + dx;
+        let mut curr_y = y2 + dy;
+        while is_in_boundaries(&(curr_x as usize, curr_y as usize), boundaries) {
+            antinodes.insert((curr_x as usize, curr_y as usize));
+            curr_x += dx;
+            curr_y += dy;
+        }
+
+        let mut curr_x = x1 - dx;
+        let mut curr_y = y1 - dy;
+        while is_in_boundaries(&(curr_x as usize, curr_y as usize), boundaries) {
+            antinodes.insert((curr_x as usize, curr_y as usize));
+            curr_x -= dx;
+            curr_y -= dy;
+        }
+        antinodes
+    }
+
+    fn generate_combinations(positions: &[Position]) -> Vec<(&Position, &Position)> {
+        let mut pairs = Vec::new();
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                pairs.push((&positions[i], &positions[j]));
+            }
+        }
+        pairs
+    }
+
+    fn count_antinodes_reference(
+        frequency_map: &FrequencyMap,
+        boundaries: &Boundaries,
+        antinodes: fn(&Position, &Position, &Boundaries) -> HashSet<Position>,
+    ) -> usize {
+        frequency_map
+            .values()
+            .flat_map(|positions| generate_combinations(positions))
+            .flat_map(|(p1, p2)| antinodes(p1, p2, boundaries))
+            .collect::<HashSet<Position>>()
+            .len()
+    }
+
     #[test]
     fn test_find_antinodes() {
         let position_1: Position = (3, 4);
@@ -227,6 +518,152 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_find_antinodes_with_presets() {
+        let boundaries: Boundaries = (10, 10);
+        let pairs = vec![
+            ((3, 4), (5, 5)),
+            ((0, 0), (1, 3)),
+            ((0, 0), (2, 1)),
+            ((1, 3), (2, 1)),
+        ];
+
+        for (position_1, position_2) in pairs {
+            assert_eq!(
+                find_antinodes_with(
+                    &position_1,
+                    &position_2,
+                    &boundaries,
+                    &ResonanceConfig::part_1()
+                ),
+                find_antinodes(&position_1, &position_2, &boundaries),
+                "Part 1 preset differs for {:?}&{:?}",
+                position_1,
+                position_2
+            );
+            assert_eq!(
+                find_antinodes_with(
+                    &position_1,
+                    &position_2,
+                    &boundaries,
+                    &ResonanceConfig::part_2()
+                ),
+                find_antinodes_resonant(&position_1, &position_2, &boundaries),
+                "Part 2 preset differs for {:?}&{:?}",
+                position_1,
+                position_2
+            );
+        }
+
+        let mut frequency_map: FrequencyMap = HashMap::new();
+        frequency_map.insert('0', vec![(1, 8), (2, 5), (3, 7), (4, 4)]);
+        frequency_map.insert('A', vec![(5, 6), (8, 8), (9, 9)]);
+        let boundaries: Boundaries = (12, 12);
+        assert_eq!(
+            find_all_antinodes(&frequency_map, &boundaries),
+            count_antinodes_reference(&frequency_map, &boundaries, find_antinodes)
+        );
+        assert_eq!(
+            find_all_antinodes_resonant(&frequency_map, &boundaries),
+            count_antinodes_reference(&frequency_map, &boundaries, find_antinodes_resonant)
+        );
+    }
+
+    #[test]
+    fn test_find_antinodes_lattice() {
+        let boundaries: Boundaries = (10, 10);
+
+        let raw = find_antinodes_resonant(&(2, 1), &(4, 5), &boundaries);
+        let expected_raw: HashSet<Position> = [(0, -3), (2, 1), (4, 5), (6, 9)]
+            .iter()
+            .filter_map(|&(x, y)| to_position(x, y, &boundaries))
+            .collect();
+        assert_eq!(
+            raw, expected_raw,
+            "Raw step antinodes do not match expected."
+        );
+
+        let lattice =
+            find_antinodes_with(&(2, 1), &(4, 5), &boundaries, &ResonanceConfig::lattice());
+        let expected_lattice: HashSet<Position> = [(1, -1), (2, 1), (3, 3), (4, 5), (5, 7), (6, 9)]
+            .iter()
+            .filter_map(|&(x, y)| to_position(x, y, &boundaries))
+            .collect();
+        assert_eq!(
+            lattice, expected_lattice,
+            "Reduced step antinodes do not match expected."
+        );
+
+        let config = ResonanceConfig {
+            reduce_step: true,
+            harmonics: Harmonics::Ratios(vec![2]),
+        };
+        let harmonics = find_antinodes_with(&(2, 1), &(4, 5), &boundaries, &config);
+        let expected_harmonics: HashSet<Position> = [(6, 9)].iter().cloned().collect();
+        assert_eq!(
+            harmonics, expected_harmonics,
+            "Second harmonic antinodes do not match expected."
+        );
+    }
+
+    #[test]
+    fn test_antinode_report() {
+        let mut frequency_map: FrequencyMap = HashMap::new();
+        frequency_map.insert('0', vec![(1, 8), (2, 5), (3, 7), (4, 4)]);
+        frequency_map.insert('A', vec![(5, 6), (8, 8), (9, 9)]);
+        let boundaries: Boundaries = (12, 12);
+
+        let report = antinode_report(&frequency_map, &boundaries, &ResonanceConfig::part_1());
+        assert_eq!(report.total(), 14, "Report total does not match part 1.");
+        assert_eq!(report.positions('0').len(), 10);
+        assert_eq!(report.positions('A').len(), 5);
+        assert!(report.positions('B').is_empty());
+
+        let overlaps = report.overlaps();
+        assert_eq!(
+            overlaps,
+            BTreeMap::from([((1, 3), vec!['0', 'A'])]),
+            "Overlapping antinodes do not match expected."
+        );
+        assert_eq!(
+            report.sources(&(1, 3)),
+            vec![('0', ((2, 5), (3, 7))), ('A', ((5, 6), (9, 9)))],
+            "Antinode sources do not match expected."
+        );
+        assert_eq!(report.sources(&(0, 11)), vec![('0', ((1, 8), (2, 5)))]);
+
+        let report = antinode_report(&frequency_map, &boundaries, &ResonanceConfig::part_2());
+        assert_eq!(report.total(), 34, "Report total does not match part 2.");
+    }
+
+    #[test]
+    fn test_render_map() {
+        let mut frequency_map: FrequencyMap = HashMap::new();
+        frequency_map.insert('a', vec![(3, 4), (5, 5)]);
+        let boundaries: Boundaries = (10, 10);
+
+        let report = antinode_report(&frequency_map, &boundaries, &ResonanceConfig::part_1());
+        let expected = [
+            "..........",
+            "...#......",
+            "..........",
+            "....a.....",
+            "..........",
+            ".....a....",
+            "..........",
+            "......#...",
+            "..........",
+            "..........",
+        ]
+        .join("\n");
+        let result = render_map(&frequency_map, &boundaries, &report);
+        assert_eq!(
+            result, expected,
+            "Rendered map does not match expected:\n{}",
+            result
+        );
+    }
+
     #[test]
     fn test_day08_part1_and_part2() {
         let (frequency_map, map_boundaries) =
@@ -236,5 +673,16 @@ mod tests {
 
         assert_eq!(result_1, 364, "Day 08 - Part 1 failed");
         assert_eq!(result_2, 1231, "Day 08 - Part 2 failed");
+
+        let result_1 =
+            find_all_antinodes_with(&frequency_map, &map_boundaries, &ResonanceConfig::part_1());
+        let result_2 =
+            find_all_antinodes_with(&frequency_map, &map_boundaries, &ResonanceConfig::part_2());
+        let result_lattice =
+            find_all_antinodes_with(&frequency_map, &map_boundaries, &ResonanceConfig::lattice());
+        assert_eq!(result_1, 364, "Day 08 - Part 1 preset failed");
+        assert_eq!(result_2, 1231, "Day 08 - Part 2 preset failed");
+        // Every antenna difference in the puzzle input is already primitive.
+        assert_eq!(result_lattice, 1231, "Day 08 - Lattice count failed");
     }
 }
@@ -1,6 +1,9 @@
-use std::collections::{HashSet, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
 pub fn run() {
     let memory = read_input("data/day09.txt").expect("Failed to read and parse the input file");
@@ -10,6 +13,27 @@ pub fn run() {
     println!("Day 09 - part 2: {}", result_2);
 }
 
+pub fn run_strategies() {
+    let memory = read_input("data/day09.txt").expect("Failed to read and parse the input file");
+    let layout = DiskLayout::from_dense(&memory);
+    println!(
+        "{:<14} {:>13} {:>12} {:>13} {:>12} {:>16}",
+        "strategy", "blocks moved", "files moved", "fragmentation", "largest free", "checksum"
+    );
+    for strategy in all_strategies() {
+        let report = run_strategy(&layout, strategy);
+        println!(
+            "{:<14} {:>13} {:>12} {:>13.4} {:>12} {:>16}",
+            report.strategy,
+            report.blocks_moved,
+            report.files_moved,
+            report.fragmentation_ratio,
+            report.largest_free_span,
+            report.checksum
+        );
+    }
+}
+
 pub fn read_input(path: &str) -> Result<Vec<usize>, String> {
     let content = fs::read_to_string(Path::new(path))
         .unwrap_or_else(|_| panic!("Failed to read input file: {}", path));
@@ -25,7 +49,7 @@ pub fn read_input(path: &str) -> Result<Vec<usize>, String> {
         .collect()
 }
 
-fn process_disk(memory: &[usize]) -> usize {
+pub fn process_disk(memory: &[usize]) -> usize {
     let (block_file, free_space): (Vec<_>, Vec<_>) =
         memory.iter().enumerate().partition(|&(i, _)| i % 2 == 0);
 
@@ -74,54 +98,409 @@ fn process_disk(memory: &[usize]) -> usize {
         .sum()
 }
 
-fn process_disk_whole_files(memory: &[usize]) -> usize {
-    let (block_file, free_space): (Vec<_>, Vec<_>) =
-        memory.iter().enumerate().partition(|&(i, _)| i % 2 == 0);
+pub fn process_disk_whole_files(memory: &[usize]) -> usize {
+    let max_span = memory.iter().copied().max().unwrap_or(0);
+    let mut files: Vec<(usize, usize)> = Vec::with_capacity(memory.len() / 2 + 1);
+    let mut free_spans: Vec<BinaryHeap<Reverse<usize>>> = vec![BinaryHeap::new(); max_span + 1];
+
+    let mut position = 0;
+    for (idx, &size) in memory.iter().enumerate() {
+        if idx % 2 == 0 {
+            files.push((position, size));
+        } else if size > 0 {
+            free_spans[size].push(Reverse(position));
+        }
+        position += size;
+    }
+
+    let mut checksum = 0;
+    for (id, &(start, size)) in files.iter().enumerate().rev() {
+        let mut target = start;
+        if size > 0 {
+            let leftmost = (size..=max_span)
+                .filter_map(|span| free_spans[span].peek().map(|&Reverse(pos)| (pos, span)))
+                .filter(|&(pos, _)| pos < start)
+                .min();
+            if let Some((pos, span)) = leftmost {
+                free_spans[span].pop();
+                if span > size {
+                    free_spans[span - size].push(Reverse(pos + size));
+                }
+                target = pos;
+            }
+        }
+        checksum += id * (size * target + size * size.saturating_sub(1) / 2);
+    }
+    checksum
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiskLayout {
+    blocks: Vec<Option<usize>>,
+}
+
+impl DiskLayout {
+    pub fn from_dense(memory: &[usize]) -> Self {
+        let mut blocks = Vec::with_capacity(memory.iter().sum());
+        for (idx, &size) in memory.iter().enumerate() {
+            let block = if idx % 2 == 0 { Some(idx / 2) } else { None };
+            blocks.extend(std::iter::repeat_n(block, size));
+        }
+        DiskLayout { blocks }
+    }
 
-    let free_space_lifo: Vec<usize> = free_space.into_iter().map(|(_, &value)| value).collect();
-    let block_file_lifo: Vec<usize> = block_file.into_iter().map(|(_, &value)| value).collect();
+    // Only layouts whose files are contiguous and in increasing id order have a dense form;
+    // ids missing from the layout are written as empty files.
+    pub fn to_dense(&self) -> Result<Vec<usize>, String> {
+        let mut memory: Vec<usize> = Vec::new();
+        let mut next_id = 0;
+        let mut idx = 0;
+        while idx < self.blocks.len() {
+            let block = self.blocks[idx];
+            let run = self.blocks[idx..]
+                .iter()
+                .take_while(|&&other| other == block)
+                .count();
+            match block {
+                None => {
+                    if memory.len().is_multiple_of(2) {
+                        memory.push(0);
+                        next_id += 1;
+                    }
+                    memory.push(run);
+                }
+                Some(id) => {
+                    if id < next_id {
+                        return Err(format!("File {} is fragmented or out of order", id));
+                    }
+                    if !memory.len().is_multiple_of(2) {
+                        memory.push(0);
+                    }
+                    for _ in next_id..id {
+                        memory.extend([0, 0]);
+                    }
+                    memory.push(run);
+                    next_id = id + 1;
+                }
+            }
+            idx += run;
+        }
+        Ok(memory)
+    }
 
-    let mut free_space_lifo: Vec<Vec<Option<usize>>> =
-        free_space_lifo.into_iter().map(|n| vec![None; n]).collect();
+    pub fn blocks(&self) -> impl Iterator<Item = Option<usize>> + '_ {
+        self.blocks.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
 
-    let mut rearranged_memory: Vec<Option<usize>> = Vec::new();
-    let mut idx_none: HashSet<usize> = HashSet::new();
+    pub fn checksum(&self) -> usize {
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| block.map_or(0, |id| idx * id))
+            .sum()
+    }
 
-    for (idx, last_block) in block_file_lifo.iter().enumerate().rev() {
-        for (idx_fs, free_space) in free_space_lifo.iter_mut().enumerate() {
-            if idx_fs >= idx {
+    pub fn compact_blocks(&mut self, on_step: &mut impl FnMut(&DiskLayout)) {
+        let mut left = 0;
+        let mut right = self.blocks.len();
+        loop {
+            while left < right && self.blocks[left].is_some() {
+                left += 1;
+            }
+            while right > left && self.blocks[right - 1].is_none() {
+                right -= 1;
+            }
+            if right <= left + 1 {
                 break;
             }
-            let n_free_space = free_space.iter().filter(|x| x.is_none()).count();
-            if *last_block <= n_free_space {
-                let idx_free_space = free_space.iter().position(|x| x.is_none()).unwrap();
-                for idx_insert in idx_free_space..idx_free_space + last_block {
-                    free_space[idx_insert] = Some(idx);
+            self.blocks.swap(left, right - 1);
+            on_step(self);
+        }
+    }
+
+    fn file_spans(&self) -> Vec<(usize, usize)> {
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (idx, block) in self.blocks.iter().enumerate() {
+            if let Some(id) = *block {
+                if spans.len() <= id {
+                    spans.resize(id + 1, (idx, 0));
                 }
-                for _ in 0..*last_block {
-                    free_space_lifo[idx - 1].push(None);
+                if spans[id].1 == 0 {
+                    spans[id].0 = idx;
                 }
-                idx_none.insert(idx);
-                break;
+                spans[id].1 += 1;
             }
         }
+        spans
     }
 
-    for ((idx, block), free_space) in block_file_lifo.into_iter().enumerate().zip(free_space_lifo) {
-        if !idx_none.contains(&idx) {
-            for _ in 0..block {
-                rearranged_memory.push(Some(idx));
+    fn free_spans(&self) -> Vec<(usize, usize)> {
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (idx, block) in self.blocks.iter().enumerate() {
+            if block.is_none() {
+                match spans.last_mut() {
+                    Some((start, len)) if *start + *len == idx => *len += 1,
+                    _ => spans.push((idx, 1)),
+                }
             }
         }
+        spans
+    }
 
-        rearranged_memory.extend(free_space);
+    fn move_file(&mut self, from: usize, to: usize, size: usize) {
+        for offset in 0..size {
+            self.blocks.swap(to + offset, from + offset);
+        }
     }
 
-    rearranged_memory
-        .iter()
-        .enumerate()
-        .map(|(idx, value)| value.map_or(0, |v| idx * v))
-        .sum()
+    fn free_span_before(&self, size: usize, limit: usize) -> Option<usize> {
+        let mut run = 0;
+        for idx in 0..limit {
+            if self.blocks[idx].is_none() {
+                run += 1;
+                if run == size {
+                    return Some(idx + 1 - size);
+                }
+            } else {
+                run = 0;
+            }
+        }
+        None
+    }
+
+    pub fn compact_files(&mut self, on_step: &mut impl FnMut(&DiskLayout)) {
+        for (start, size) in self.file_spans().into_iter().rev() {
+            if size == 0 {
+                continue;
+            }
+            if let Some(target) = self.free_span_before(size, start) {
+                self.move_file(start, target, size);
+                on_step(self);
+            }
+        }
+    }
+}
+
+// Layouts with single digit ids are rendered as in the puzzle examples; once an id needs
+// more digits, every block ends with a comma so that even a single block parses back.
+impl fmt::Display for DiskLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terminator = if self.blocks.iter().flatten().any(|&id| id >= 10) {
+            ","
+        } else {
+            ""
+        };
+        for block in self.blocks.iter() {
+            match block {
+                Some(id) => write!(f, "{}", id)?,
+                None => write!(f, ".")?,
+            }
+            write!(f, "{}", terminator)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DiskLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parse_block = |token: &str| match token {
+            "." => Ok(None),
+            _ => token
+                .parse::<usize>()
+                .map(Some)
+                .map_err(|_| format!("Invalid block '{}'", token)),
+        };
+        let blocks = if s.contains(',') {
+            s.split_terminator(',')
+                .map(|token| parse_block(token.trim()))
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            s.chars()
+                .map(|c| parse_block(&c.to_string()))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        Ok(DiskLayout { blocks })
+    }
+}
+
+pub trait CompactionStrategy {
+    fn name(&self) -> &str;
+
+    fn compact(&self, layout: &mut DiskLayout) -> Moves;
+}
+
+// A file counts as moved as soon as one of its blocks changed position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Moves {
+    pub blocks: usize,
+    pub files: usize,
+}
+
+pub struct BlockByBlock;
+pub struct FirstFit;
+pub struct BestFit;
+pub struct WorstFit;
+pub struct Defragment;
+
+pub fn all_strategies() -> Vec<&'static dyn CompactionStrategy> {
+    vec![&BlockByBlock, &FirstFit, &BestFit, &WorstFit, &Defragment]
+}
+
+#[derive(Clone, Copy)]
+enum Fit {
+    First,
+    Best,
+    Worst,
+}
+
+// Files only move left and are processed right to left, so the space they free is never
+// a candidate for the files still to move and the free spans only ever shrink.
+fn compact_fit(layout: &mut DiskLayout, fit: Fit) -> Moves {
+    let mut free_spans = layout.free_spans();
+    let mut moves = Moves::default();
+    for (start, size) in layout.file_spans().into_iter().rev() {
+        if size == 0 {
+            continue;
+        }
+        let mut candidates = free_spans
+            .iter()
+            .enumerate()
+            .take_while(|(_, &(pos, _))| pos < start)
+            .filter(|(_, &(_, len))| len >= size);
+        let chosen = match fit {
+            Fit::First => candidates.next(),
+            Fit::Best => candidates.min_by_key(|(_, &(pos, len))| (len, pos)),
+            Fit::Worst => candidates.max_by_key(|(_, &(pos, len))| (len, Reverse(pos))),
+        };
+        if let Some((idx, &(pos, len))) = chosen {
+            layout.move_file(start, pos, size);
+            free_spans[idx] = (pos + size, len - size);
+            moves.blocks += size;
+            moves.files += 1;
+        }
+    }
+    moves
+}
+
+impl CompactionStrategy for BlockByBlock {
+    fn name(&self) -> &str {
+        "block-by-block"
+    }
+
+    fn compact(&self, layout: &mut DiskLayout) -> Moves {
+        let before = layout.blocks.clone();
+        layout.compact_blocks(&mut |_| {});
+        // Blocks only ever move into free slots, so every changed file block was moved.
+        let mut moved: Vec<usize> = before
+            .iter()
+            .zip(layout.blocks.iter())
+            .filter(|(old, new)| old != new)
+            .filter_map(|(_, &new)| new)
+            .collect();
+        let blocks = moved.len();
+        moved.sort_unstable();
+        moved.dedup();
+        Moves {
+            blocks,
+            files: moved.len(),
+        }
+    }
+}
+
+impl CompactionStrategy for FirstFit {
+    fn name(&self) -> &str {
+        "first-fit"
+    }
+
+    fn compact(&self, layout: &mut DiskLayout) -> Moves {
+        compact_fit(layout, Fit::First)
+    }
+}
+
+impl CompactionStrategy for BestFit {
+    fn name(&self) -> &str {
+        "best-fit"
+    }
+
+    fn compact(&self, layout: &mut DiskLayout) -> Moves {
+        compact_fit(layout, Fit::Best)
+    }
+}
+
+impl CompactionStrategy for WorstFit {
+    fn name(&self) -> &str {
+        "worst-fit"
+    }
+
+    fn compact(&self, layout: &mut DiskLayout) -> Moves {
+        compact_fit(layout, Fit::Worst)
+    }
+}
+
+impl CompactionStrategy for Defragment {
+    fn name(&self) -> &str {
+        "defragment"
+    }
+
+    fn compact(&self, layout: &mut DiskLayout) -> Moves {
+        let mut blocks = Vec::with_capacity(layout.blocks.len());
+        let mut moves = Moves::default();
+        for (id, (start, size)) in layout.file_spans().into_iter().enumerate() {
+            if size > 0 && start != blocks.len() {
+                moves.blocks += size;
+                moves.files += 1;
+            }
+            blocks.extend(std::iter::repeat_n(Some(id), size));
+        }
+        blocks.resize(layout.blocks.len(), None);
+        layout.blocks = blocks;
+        moves
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CompactionReport {
+    pub strategy: String,
+    pub blocks_moved: usize,
+    pub files_moved: usize,
+    pub fragmentation_ratio: f64,
+    pub largest_free_span: usize,
+    pub checksum: usize,
+}
+
+// The fragmentation ratio is the share of free blocks lying outside the largest free span.
+pub fn run_strategy(layout: &DiskLayout, strategy: &dyn CompactionStrategy) -> CompactionReport {
+    let mut layout = layout.clone();
+    let moves = strategy.compact(&mut layout);
+
+    let free_spans = layout.free_spans();
+    let total_free: usize = free_spans.iter().map(|&(_, len)| len).sum();
+    let largest_free_span = free_spans.iter().map(|&(_, len)| len).max().unwrap_or(0);
+    let fragmentation_ratio = if total_free == 0 {
+        0.0
+    } else {
+        1.0 - largest_free_span as f64 / total_free as f64
+    };
+
+    CompactionReport {
+        strategy: strategy.name().to_string(),
+        blocks_moved: moves.blocks,
+        files_moved: moves.files,
+        fragmentation_ratio,
+        largest_free_span,
+        checksum: layout.checksum(),
+    }
 }
 
 #[cfg(test)]
@@ -142,6 +521,221 @@ mod tests {
         assert_eq!(2858, result, "Test process disk failed");
     }
 
+    #[test]
+    fn test_process_disk_whole_files_edge_cases() {
+        let test_cases = vec![
+            (vec![1, 0, 1], 1),
+            (vec![1, 2, 0, 1, 1], 2),
+            (vec![2, 1, 1, 1, 2], 24),
+            (vec![1], 0),
+        ];
+        for (memory, expected) in test_cases {
+            let result = process_disk_whole_files(&memory);
+            assert_eq!(
+                result, expected,
+                "Test process disk whole files failed for {:?}",
+                memory
+            );
+        }
+    }
+
+    #[test]
+    fn test_process_disk_whole_files_wide_spans() {
+        let memories: Vec<Vec<usize>> = vec![
+            vec![1, 12, 10, 3, 2],
+            vec![3, 15, 4, 10, 12, 1, 2],
+            vec![25, 40, 11, 2, 30],
+        ];
+        for memory in memories {
+            let mut layout = DiskLayout::from_dense(&memory);
+            layout.compact_files(&mut |_| {});
+            assert_eq!(
+                process_disk_whole_files(&memory),
+                layout.checksum(),
+                "Test process disk whole files failed for {:?}",
+                memory
+            );
+        }
+        assert_eq!(process_disk_whole_files(&[1, 12, 10, 3, 2]), 81);
+    }
+
+    #[test]
+    fn test_disk_layout_render() {
+        let layout = DiskLayout::from_dense(&[1, 2, 3, 4, 5]);
+        assert_eq!(layout.to_string(), "0..111....22222");
+
+        let layout =
+            DiskLayout::from_dense(&[2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2]);
+        assert_eq!(
+            layout.to_string(),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        assert_eq!(layout.len(), 42);
+        assert_eq!(layout.blocks().filter(|block| block.is_none()).count(), 14);
+    }
+
+    #[test]
+    fn test_disk_layout_round_trip() {
+        let memories: Vec<Vec<usize>> = vec![
+            vec![1, 2, 3, 4, 5],
+            vec![2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2],
+            vec![3, 0, 2, 4],
+        ];
+        for memory in memories {
+            let layout = DiskLayout::from_dense(&memory);
+            assert_eq!(
+                layout.to_dense(),
+                Ok(memory.clone()),
+                "Dense round trip failed for {:?}",
+                memory
+            );
+            let parsed: DiskLayout = layout.to_string().parse().expect("Failed to parse layout");
+            assert_eq!(parsed, layout, "Text round trip failed for {:?}", memory);
+        }
+
+        let layout = DiskLayout::from_dense(&[1, 2, 0, 1, 1]);
+        assert_eq!(layout.to_dense(), Ok(vec![1, 3, 0, 0, 1]));
+
+        let memory: Vec<usize> = vec![
+            1, 1, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 2, 1, 1,
+        ];
+        let layout = DiskLayout::from_dense(&memory);
+        assert_eq!(layout.to_string(), "0,.,1,2,3,4,5,6,7,8,9,10,10,.,11,");
+        let mut compacted = layout.clone();
+        compacted.compact_files(&mut |_| {});
+        let mut memory = vec![0; 20];
+        memory.push(1);
+        let single = DiskLayout::from_dense(&memory);
+        assert_eq!(single.to_string(), "10,");
+        for layout in [layout, compacted, single] {
+            let parsed: DiskLayout = layout.to_string().parse().expect("Failed to parse layout");
+            assert_eq!(parsed, layout, "Text round trip failed for {}", layout);
+        }
+        assert!("0,.,x,".parse::<DiskLayout>().is_err());
+
+        for (text, dense) in [("..1", Ok(vec![0, 2, 1])), ("..0", Err(()))] {
+            let layout: DiskLayout = text.parse().unwrap();
+            assert_eq!(
+                layout.to_dense().map_err(|_| ()),
+                dense,
+                "Dense form failed for {}",
+                text
+            );
+            if let Ok(memory) = dense {
+                assert_eq!(DiskLayout::from_dense(&memory).to_string(), text);
+            }
+        }
+
+        let layout: DiskLayout = "0..1.0".parse().unwrap();
+        assert!(
+            layout.to_dense().is_err(),
+            "Fragmented file has no dense form"
+        );
+    }
+
+    #[test]
+    fn test_compact_blocks_snapshots() {
+        let mut layout = DiskLayout::from_dense(&[1, 2, 3, 4, 5]);
+        let mut snapshots = vec![layout.to_string()];
+        layout.compact_blocks(&mut |step| snapshots.push(step.to_string()));
+        let expected = vec![
+            "0..111....22222",
+            "02.111....2222.",
+            "022111....222..",
+            "0221112...22...",
+            "02211122..2....",
+            "022111222......",
+        ];
+        assert_eq!(snapshots, expected, "Block compaction snapshots differ");
+
+        let memory: Vec<usize> = vec![2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2];
+        let mut layout = DiskLayout::from_dense(&memory);
+        let mut snapshots = vec![layout.to_string()];
+        layout.compact_blocks(&mut |step| snapshots.push(step.to_string()));
+        assert_eq!(snapshots.len(), 13);
+        assert_eq!(snapshots[1], "009..111...2...333.44.5555.6666.777.88889.");
+        assert_eq!(snapshots[12], "0099811188827773336446555566..............");
+        assert_eq!(layout.checksum(), 1928);
+    }
+
+    #[test]
+    fn test_compact_files_snapshots() {
+        let memory: Vec<usize> = vec![2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2];
+        let mut layout = DiskLayout::from_dense(&memory);
+        let mut snapshots = vec![layout.to_string()];
+        layout.compact_files(&mut |step| snapshots.push(step.to_string()));
+        let expected = vec![
+            "00...111...2...333.44.5555.6666.777.888899",
+            "0099.111...2...333.44.5555.6666.777.8888..",
+            "0099.1117772...333.44.5555.6666.....8888..",
+            "0099.111777244.333....5555.6666.....8888..",
+            "00992111777.44.333....5555.6666.....8888..",
+        ];
+        assert_eq!(snapshots, expected, "File compaction snapshots differ");
+        assert_eq!(layout.checksum(), 2858);
+    }
+
+    #[test]
+    fn test_compaction_strategies() {
+        let memory: Vec<usize> = vec![2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2];
+        let layout = DiskLayout::from_dense(&memory);
+
+        let report = run_strategy(&layout, &BlockByBlock);
+        assert_eq!((report.blocks_moved, report.checksum), (12, 1928));
+        assert_eq!(report.files_moved, 4);
+        assert_eq!(report.largest_free_span, 14);
+        assert_eq!(report.fragmentation_ratio, 0.0);
+
+        let report = run_strategy(&layout, &FirstFit);
+        assert_eq!((report.files_moved, report.checksum), (4, 2858));
+        assert_eq!(report.blocks_moved, 8);
+        assert_eq!(report.largest_free_span, 5);
+
+        let report = run_strategy(&layout, &BestFit);
+        assert_eq!((report.files_moved, report.checksum), (4, 2858));
+        assert_eq!(report.blocks_moved, 8);
+
+        let mut defragmented = layout.clone();
+        assert_eq!(
+            Defragment.compact(&mut defragmented),
+            Moves {
+                blocks: 26,
+                files: 9
+            }
+        );
+        assert_eq!(
+            defragmented.to_string(),
+            "0011123334455556666777888899.............."
+        );
+        let report = run_strategy(&layout, &Defragment);
+        assert_eq!(report.fragmentation_ratio, 0.0);
+        assert_eq!(report.largest_free_span, 14);
+
+        let layout = DiskLayout::from_dense(&[1, 3, 1, 1, 1, 2, 1]);
+        assert_eq!(layout.to_string(), "0...1.2..3");
+        let expected = vec![
+            ("first-fit", "0321......"),
+            ("best-fit", "021..3...."),
+            ("worst-fit", "0321......"),
+        ];
+        let strategies: Vec<&dyn CompactionStrategy> = vec![&FirstFit, &BestFit, &WorstFit];
+        for (strategy, (name, expected)) in strategies.into_iter().zip(expected) {
+            let mut compacted = layout.clone();
+            assert_eq!(
+                strategy.compact(&mut compacted),
+                Moves {
+                    blocks: 3,
+                    files: 3
+                }
+            );
+            assert_eq!(strategy.name(), name);
+            assert_eq!(compacted.to_string(), expected, "Strategy {} failed", name);
+        }
+        let report = run_strategy(&layout, &BestFit);
+        assert_eq!(report.largest_free_span, 4);
+        assert!((report.fragmentation_ratio - 1.0 / 3.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_day09_part1_and_part2() {
         let memory = read_input("data/day09.txt").expect("Failed to read and parse the input file");
@@ -150,5 +744,20 @@ mod tests {
 
         assert_eq!(result_1, 6367087064415, "Day 09 - Part 1 failed");
         assert_eq!(result_2, 6390781891880, "Day 09 - Part 2 failed");
+
+        let mut layout = DiskLayout::from_dense(&memory);
+        layout.compact_blocks(&mut |_| {});
+        assert_eq!(
+            layout.checksum(),
+            6367087064415,
+            "Day 09 - Part 1 layout failed"
+        );
+        let mut layout = DiskLayout::from_dense(&memory);
+        layout.compact_files(&mut |_| {});
+        assert_eq!(
+            layout.checksum(),
+            6390781891880,
+            "Day 09 - Part 2 layout failed"
+        );
     }
 }
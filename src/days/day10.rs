@@ -1,11 +1,11 @@
-use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::path::Path;
 
 type TopographicMap = Vec<Vec<usize>>;
 type Position = (usize, usize);
 type Boundaries = (usize, usize);
+pub type GeneralMap = Vec<Vec<Option<usize>>>;
 
 pub fn run() {
     let map = read_input("data/day10.txt").expect("Failed to read and parse the input file");
@@ -15,6 +15,12 @@ pub fn run() {
     println!("Day 10 - part 2: {}", result_2);
 }
 
+pub fn run_json() {
+    let map = read_input("data/day10.txt").expect("Failed to read and parse the input file");
+    let report = trail_report(&to_general_map(&map), &TrailRules::puzzle(), 20);
+    println!("{}", report.to_json(10));
+}
+
 pub fn read_input(path: &str) -> Result<TopographicMap, String> {
     let content = fs::read_to_string(Path::new(path))
         .unwrap_or_else(|_| panic!("Failed to read input file: {}", path));
@@ -56,14 +62,14 @@ fn get_next_positions(
     next_positions
 }
 
-fn find_paths(
+pub fn find_paths(
     starting_position: &Position,
     map: &TopographicMap,
     boundaries: &Boundaries,
 ) -> usize {
     const MAX_HEIGHT: usize = 9;
     let mut height = map[starting_position.0][starting_position.1];
-    if !height == 0 {
+    if height != 0 {
         return 0;
     }
     let mut trails: HashSet<Position> = HashSet::new();
@@ -82,14 +88,14 @@ fn find_paths(
     trails.len()
 }
 
-fn find_ratings(
+pub fn find_ratings(
     starting_position: &Position,
     map: &TopographicMap,
     boundaries: &Boundaries,
 ) -> usize {
     const MAX_HEIGHT: usize = 9;
     let mut height = map[starting_position.0][starting_position.1];
-    if !height == 0 {
+    if height != 0 {
         return 0;
     }
     let mut trails: Vec<Position> = vec![*starting_position];
@@ -107,56 +113,546 @@ fn find_ratings(
     trails.len()
 }
 
-pub fn find_all_paths(map: &TopographicMap) -> usize {
-    let boundaries = (map.len(), map[0].len());
-    let starting_points: Vec<Position> = map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailheadStats {
+    pub position: Position,
+    pub score: usize,
+    pub rating: usize,
+}
+
+// Ratings come from a pass over the heights from the summits down. Scores need the set of
+// summits each trailhead reaches, so they use one search per trailhead sharing a stamp array.
+pub fn trailhead_stats(map: &TopographicMap) -> Vec<TrailheadStats> {
+    const MAX_HEIGHT: usize = 9;
+    let boundaries = (map.len(), map.first().map_or(0, |row| row.len()));
+    let index = |position: &Position| position.0 * boundaries.1 + position.1;
+
+    let mut levels: Vec<Vec<Position>> = vec![Vec::new(); MAX_HEIGHT + 1];
+    for (index_r, row) in map.iter().enumerate() {
+        for (index_c, &height) in row.iter().enumerate() {
+            if height <= MAX_HEIGHT {
+                levels[height].push((index_r, index_c));
+            }
+        }
+    }
+
+    let n_cells = boundaries.0 * boundaries.1;
+    let mut ratings = vec![0usize; n_cells];
+    for position in levels[MAX_HEIGHT].iter() {
+        ratings[index(position)] = 1;
+    }
+    for height in (0..MAX_HEIGHT).rev() {
+        for position in levels[height].iter() {
+            let cell = index(position);
+            for next in get_next_positions(position, map, &boundaries, &height) {
+                ratings[cell] += ratings[index(&next)];
+            }
+        }
+    }
+
+    let mut stamps = vec![usize::MAX; n_cells];
+    let mut stack: Vec<Position> = Vec::new();
+    levels[0]
         .iter()
         .enumerate()
-        .flat_map(|(index_r, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(move |(index_c, &height)| {
-                    if height == 0 {
-                        Some((index_r, index_c))
-                    } else {
-                        None
+        .map(|(trailhead, position)| {
+            let mut score = 0;
+            stamps[index(position)] = trailhead;
+            stack.push(*position);
+            while let Some(current) = stack.pop() {
+                let height = map[current.0][current.1];
+                if height == MAX_HEIGHT {
+                    score += 1;
+                    continue;
+                }
+                for next in get_next_positions(&current, map, &boundaries, &height) {
+                    if stamps[index(&next)] != trailhead {
+                        stamps[index(&next)] = trailhead;
+                        stack.push(next);
                     }
-                })
+                }
+            }
+            TrailheadStats {
+                position: *position,
+                score,
+                rating: ratings[index(position)],
+            }
         })
-        .collect();
-
-    let trailheads: Vec<usize> = starting_points
-        .par_iter()
-        .map(|&position| find_paths(&position, map, &boundaries))
-        .collect();
+        .collect()
+}
 
-    trailheads.into_iter().sum()
+pub fn find_all_paths(map: &TopographicMap) -> usize {
+    trailhead_stats(map).iter().map(|stats| stats.score).sum()
 }
 
 pub fn find_all_ratings(map: &TopographicMap) -> usize {
-    let boundaries = (map.len(), map[0].len());
-    let starting_points: Vec<Position> = map
+    trailhead_stats(map).iter().map(|stats| stats.rating).sum()
+}
+
+pub enum Neighbourhood {
+    Four,
+    Eight,
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Neighbourhood::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+pub struct TrailRules {
+    pub step: Box<dyn Fn(usize, usize) -> bool>,
+    pub starts: HashSet<usize>,
+    pub ends: HashSet<usize>,
+    pub neighbourhood: Neighbourhood,
+}
+
+impl TrailRules {
+    pub fn puzzle() -> Self {
+        TrailRules {
+            step: Box::new(|from, to| to == from + 1),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([9]),
+            neighbourhood: Neighbourhood::Four,
+        }
+    }
+}
+
+// Cells are single digits, or whitespace-separated numbers when a line contains spaces;
+// `.` marks an impassable cell in both forms.
+pub fn parse_map(content: &str) -> Result<GeneralMap, String> {
+    let parse_cell = |cell: &str| {
+        if cell == "." {
+            Ok(None)
+        } else {
+            cell.parse::<usize>()
+                .map(Some)
+                .map_err(|e| format!("Failed to parse height '{}': {}", cell, e))
+        }
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            if line.trim().contains(char::is_whitespace) {
+                line.split_whitespace().map(parse_cell).collect()
+            } else {
+                line.trim()
+                    .char_indices()
+                    .map(|(idx, c)| parse_cell(&line.trim()[idx..idx + c.len_utf8()]))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+pub fn read_general_input(path: &str) -> Result<GeneralMap, String> {
+    let content = fs::read_to_string(Path::new(path))
+        .map_err(|e| format!("Failed to read input file '{}': {}", path, e))?;
+    parse_map(&content)
+}
+
+pub fn to_general_map(map: &TopographicMap) -> GeneralMap {
+    map.iter()
+        .map(|row| row.iter().map(|&height| Some(height)).collect())
+        .collect()
+}
+
+fn general_height(map: &GeneralMap, position: &Position) -> Option<usize> {
+    map.get(position.0)?.get(position.1).copied().flatten()
+}
+
+fn general_next_positions(
+    map: &GeneralMap,
+    position: &Position,
+    rules: &TrailRules,
+) -> Vec<Position> {
+    let height = match general_height(map, position) {
+        Some(height) => height,
+        None => return Vec::new(),
+    };
+    rules
+        .neighbourhood
+        .offsets()
         .iter()
-        .enumerate()
-        .flat_map(|(index_r, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(move |(index_c, &height)| {
-                    if height == 0 {
-                        Some((index_r, index_c))
-                    } else {
-                        None
-                    }
-                })
+        .filter_map(|&(dx, dy)| {
+            let row = position.0.checked_add_signed(dx)?;
+            let col = position.1.checked_add_signed(dy)?;
+            let next_height = general_height(map, &(row, col))?;
+            if (rules.step)(height, next_height) {
+                Some((row, col))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn general_previous_positions(
+    map: &GeneralMap,
+    position: &Position,
+    rules: &TrailRules,
+) -> Vec<Position> {
+    let height = match general_height(map, position) {
+        Some(height) => height,
+        None => return Vec::new(),
+    };
+    rules
+        .neighbourhood
+        .offsets()
+        .iter()
+        .filter_map(|&(dx, dy)| {
+            let row = position.0.checked_add_signed(dx)?;
+            let col = position.1.checked_add_signed(dy)?;
+            let previous_height = general_height(map, &(row, col))?;
+            if !rules.ends.contains(&previous_height) && (rules.step)(previous_height, height) {
+                Some((row, col))
+            } else {
+                None
+            }
         })
+        .collect()
+}
+
+fn is_start(map: &GeneralMap, position: &Position, rules: &TrailRules) -> bool {
+    general_height(map, position).is_some_and(|height| rules.starts.contains(&height))
+}
+
+fn is_end(map: &GeneralMap, position: &Position, rules: &TrailRules) -> bool {
+    general_height(map, position).is_some_and(|height| rules.ends.contains(&height))
+}
+
+// Summits reachable from `start`, each with the length of the shortest trail reaching it.
+pub fn reachable_summits(
+    map: &GeneralMap,
+    start: &Position,
+    rules: &TrailRules,
+) -> Vec<(Position, usize)> {
+    if !is_start(map, start, rules) {
+        return Vec::new();
+    }
+    let mut visited: HashSet<Position> = HashSet::from([*start]);
+    let mut queue: VecDeque<(Position, usize)> = VecDeque::from([(*start, 0)]);
+    let mut summits = Vec::new();
+    while let Some((position, distance)) = queue.pop_front() {
+        if is_end(map, &position, rules) {
+            summits.push((position, distance));
+            continue;
+        }
+        for next in general_next_positions(map, &position, rules) {
+            if visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    summits.sort();
+    summits
+}
+
+pub fn general_score(map: &GeneralMap, start: &Position, rules: &TrailRules) -> usize {
+    reachable_summits(map, start, rules).len()
+}
+
+// Cells from which some trail reaches a summit, found by walking the step rule backwards.
+fn summit_reach(map: &GeneralMap, rules: &TrailRules) -> Vec<Vec<bool>> {
+    let mut stack: Vec<Position> = map
+        .iter()
+        .enumerate()
+        .flat_map(|(index_r, row)| (0..row.len()).map(move |index_c| (index_r, index_c)))
+        .filter(|position| is_end(map, position, rules))
         .collect();
+    let mut reach: Vec<Vec<bool>> = map.iter().map(|row| vec![false; row.len()]).collect();
+    for position in stack.iter() {
+        reach[position.0][position.1] = true;
+    }
+    while let Some(position) = stack.pop() {
+        for previous in general_previous_positions(map, &position, rules) {
+            if !reach[previous.0][previous.1] {
+                reach[previous.0][previous.1] = true;
+                stack.push(previous);
+            }
+        }
+    }
+    reach
+}
+
+pub type Trail = Vec<Position>;
+
+// Depth-first enumeration of trails from `start`, stopping once `cap` trails are found.
+// Cells already on the current trail are skipped and only cells that can reach a summit are
+// entered. Cyclic rules can still lead it into dead ends, so it gives up after `cap` steps
+// per map cell.
+pub fn enumerate_trails(
+    map: &GeneralMap,
+    start: &Position,
+    rules: &TrailRules,
+    cap: usize,
+) -> Vec<Trail> {
+    collect_trails(map, start, rules, cap, &summit_reach(map, rules))
+}
+
+fn collect_trails(
+    map: &GeneralMap,
+    start: &Position,
+    rules: &TrailRules,
+    cap: usize,
+    reach: &[Vec<bool>],
+) -> Vec<Trail> {
+    struct Search<'a> {
+        map: &'a GeneralMap,
+        rules: &'a TrailRules,
+        reach: &'a [Vec<bool>],
+        cap: usize,
+        budget: usize,
+        trails: Vec<Trail>,
+    }
+
+    fn extend(search: &mut Search, trail: &mut Trail) {
+        let position = *trail.last().unwrap();
+        if is_end(search.map, &position, search.rules) {
+            search.trails.push(trail.clone());
+            return;
+        }
+        for next in general_next_positions(search.map, &position, search.rules) {
+            if search.trails.len() >= search.cap || search.budget == 0 {
+                return;
+            }
+            if !search.reach[next.0][next.1] || trail.contains(&next) {
+                continue;
+            }
+            search.budget -= 1;
+            trail.push(next);
+            extend(search, trail);
+            trail.pop();
+        }
+    }
+
+    if cap == 0 || !is_start(map, start, rules) || !reach[start.0][start.1] {
+        return Vec::new();
+    }
+    let mut search = Search {
+        map,
+        rules,
+        reach,
+        cap,
+        budget: cap.saturating_mul(map.iter().map(|row| row.len()).sum()),
+        trails: Vec::new(),
+    };
+    extend(&mut search, &mut vec![*start]);
+    search.trails
+}
+
+pub struct TrailheadSummary {
+    pub position: Position,
+    pub score: usize,
+    pub rating: Option<usize>,
+    pub summits: Vec<Position>,
+    pub shortest: Option<usize>,
+    pub longest: Option<usize>,
+    pub trails: Vec<Trail>,
+    pub truncated: bool,
+}
+
+pub struct TrailReport {
+    pub trailheads: Vec<TrailheadSummary>,
+}
 
-    let trailheads: Vec<usize> = starting_points
-        .par_iter()
-        .map(|&position| find_ratings(&position, map, &boundaries))
+// `rating` and `longest` are None when the rules let trails loop back on themselves.
+pub fn trail_report(map: &GeneralMap, rules: &TrailRules, trail_cap: usize) -> TrailReport {
+    let reach = summit_reach(map, rules);
+    let trailheads = general_trailhead_stats(map, rules)
+        .into_iter()
+        .map(|stats| {
+            let summits = reachable_summits(map, &stats.position, rules);
+            let trails = collect_trails(map, &stats.position, rules, trail_cap, &reach);
+            TrailheadSummary {
+                position: stats.position,
+                score: stats.score,
+                rating: stats.rating,
+                shortest: summits.iter().map(|&(_, distance)| distance).min(),
+                longest: stats.longest,
+                summits: summits.into_iter().map(|(position, _)| position).collect(),
+                truncated: stats.rating.is_none_or(|rating| trails.len() < rating),
+                trails,
+            }
+        })
         .collect();
+    TrailReport { trailheads }
+}
+
+impl TrailReport {
+    pub fn top_by_score(&self, n: usize) -> Vec<&TrailheadSummary> {
+        self.top_by(n, |summary| summary.score)
+    }
+
+    pub fn top_by_rating(&self, n: usize) -> Vec<&TrailheadSummary> {
+        self.top_by(n, |summary| summary.rating.unwrap_or(usize::MAX))
+    }
+
+    fn top_by(&self, n: usize, key: impl Fn(&TrailheadSummary) -> usize) -> Vec<&TrailheadSummary> {
+        let mut ranked: Vec<&TrailheadSummary> = self.trailheads.iter().collect();
+        ranked.sort_by(|a, b| key(b).cmp(&key(a)).then(a.position.cmp(&b.position)));
+        ranked.truncate(n);
+        ranked
+    }
+
+    pub fn to_json(&self, top: usize) -> String {
+        let position = |p: &Position| format!("[{},{}]", p.0, p.1);
+        let list = |items: Vec<String>| format!("[{}]", items.join(","));
+        let optional = |value: Option<usize>| value.map_or("null".to_string(), |v| v.to_string());
+
+        let trailheads = self
+            .trailheads
+            .iter()
+            .map(|summary| {
+                format!(
+                    "{{\"position\":{},\"score\":{},\"rating\":{},\"summits\":{},\"shortest\":{},\"longest\":{},\"truncated\":{},\"trails\":{}}}",
+                    position(&summary.position),
+                    summary.score,
+                    optional(summary.rating),
+                    list(summary.summits.iter().map(position).collect()),
+                    optional(summary.shortest),
+                    optional(summary.longest),
+                    summary.truncated,
+                    list(
+                        summary
+                            .trails
+                            .iter()
+                            .map(|trail| list(trail.iter().map(position).collect()))
+                            .collect()
+                    ),
+                )
+            })
+            .collect();
+        let ranking = |ranked: Vec<&TrailheadSummary>| {
+            list(
+                ranked
+                    .iter()
+                    .map(|summary| position(&summary.position))
+                    .collect(),
+            )
+        };
+        format!(
+            "{{\"trailheads\":{},\"top_by_score\":{},\"top_by_rating\":{}}}",
+            list(trailheads),
+            ranking(self.top_by_score(top)),
+            ranking(self.top_by_rating(top)),
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+enum TrailCount {
+    Unvisited,
+    InProgress,
+    Done(Option<(usize, usize)>),
+}
+
+// A rating counts the trails from a cell to a summit, only entering cells in `reach`. When the
+// step rule lets such a trail loop back on itself there are unboundedly many, counted as None.
+// The same pass finds the length of the longest trail.
+fn count_trails(
+    map: &GeneralMap,
+    start: &Position,
+    rules: &TrailRules,
+    reach: &[Vec<bool>],
+    memo: &mut [Vec<TrailCount>],
+) -> Option<(usize, Option<usize>)> {
+    if !reach[start.0][start.1] {
+        return Some((0, None));
+    }
+    let next_positions = |position: &Position| {
+        general_next_positions(map, position, rules)
+            .into_iter()
+            .filter(|next| reach[next.0][next.1])
+    };
+    let mut stack: Vec<(Position, bool)> = vec![(*start, false)];
+    while let Some((position, expanded)) = stack.pop() {
+        let (row, col) = position;
+        if expanded {
+            let total = next_positions(&position).try_fold((0, 0), |(count, longest), next| {
+                match memo[next.0][next.1] {
+                    TrailCount::Done(Some((next_count, next_longest))) => {
+                        Some((count + next_count, longest.max(next_longest + 1)))
+                    }
+                    _ => None,
+                }
+            });
+            memo[row][col] = TrailCount::Done(total);
+            continue;
+        }
+        if !matches!(memo[row][col], TrailCount::Unvisited) {
+            continue;
+        }
+        if is_end(map, &position, rules) {
+            memo[row][col] = TrailCount::Done(Some((1, 0)));
+            continue;
+        }
+        memo[row][col] = TrailCount::InProgress;
+        stack.push((position, true));
+        for next in next_positions(&position) {
+            stack.push((next, false));
+        }
+    }
+    match memo[start.0][start.1] {
+        TrailCount::Done(trails) => trails.map(|(count, longest)| (count, Some(longest))),
+        _ => Some((0, None)),
+    }
+}
+
+fn unvisited(map: &GeneralMap) -> Vec<Vec<TrailCount>> {
+    map.iter()
+        .map(|row| vec![TrailCount::Unvisited; row.len()])
+        .collect()
+}
+
+pub fn general_rating(map: &GeneralMap, start: &Position, rules: &TrailRules) -> Option<usize> {
+    if !is_start(map, start, rules) {
+        return Some(0);
+    }
+    let reach = summit_reach(map, rules);
+    count_trails(map, start, rules, &reach, &mut unvisited(map)).map(|(count, _)| count)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneralTrailheadStats {
+    pub position: Position,
+    pub score: usize,
+    pub rating: Option<usize>,
+    pub longest: Option<usize>,
+}
 
-    trailheads.into_iter().sum()
+pub fn general_trailhead_stats(map: &GeneralMap, rules: &TrailRules) -> Vec<GeneralTrailheadStats> {
+    let reach = summit_reach(map, rules);
+    let mut memo = unvisited(map);
+    let mut stats = Vec::new();
+    for (index_r, row) in map.iter().enumerate() {
+        for index_c in 0..row.len() {
+            let position = (index_r, index_c);
+            if is_start(map, &position, rules) {
+                let trails = count_trails(map, &position, rules, &reach, &mut memo);
+                stats.push(GeneralTrailheadStats {
+                    position,
+                    score: general_score(map, &position, rules),
+                    rating: trails.map(|(count, _)| count),
+                    longest: trails.and_then(|(_, longest)| longest),
+                });
+            }
+        }
+    }
+    stats
 }
 
 #[cfg(test)]
@@ -208,6 +704,251 @@ mod tests {
         let result = find_all_ratings(&map);
         assert_eq!(81, result, "Test find paths failed");
     }
+    #[test]
+    fn test_find_paths_rejects_non_zero_start() {
+        let map: TopographicMap = vec![
+            vec![0, 1, 2, 3],
+            vec![1, 2, 3, 4],
+            vec![8, 7, 6, 5],
+            vec![9, 8, 7, 6],
+        ];
+        let boundaries = (4, 4);
+        for position in [(0, 1), (1, 1), (3, 0)] {
+            assert_eq!(
+                0,
+                find_paths(&position, &map, &boundaries),
+                "Test find paths should reject start {:?}",
+                position
+            );
+            assert_eq!(
+                0,
+                find_ratings(&position, &map, &boundaries),
+                "Test find ratings should reject start {:?}",
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn test_trailhead_stats() {
+        let map: TopographicMap = vec![
+            vec![8, 9, 0, 1, 0, 1, 2, 3],
+            vec![7, 8, 1, 2, 1, 8, 7, 4],
+            vec![8, 7, 4, 3, 0, 9, 6, 5],
+            vec![9, 6, 5, 4, 9, 8, 7, 4],
+            vec![4, 5, 6, 7, 8, 9, 0, 3],
+            vec![3, 2, 0, 1, 9, 0, 1, 2],
+            vec![0, 1, 3, 2, 9, 8, 0, 1],
+            vec![1, 0, 4, 5, 6, 7, 3, 2],
+        ];
+        let boundaries = (8, 8);
+        let stats = trailhead_stats(&map);
+        let scores: Vec<usize> = stats.iter().map(|stats| stats.score).collect();
+        let ratings: Vec<usize> = stats.iter().map(|stats| stats.rating).collect();
+        assert_eq!(scores, vec![5, 6, 5, 3, 1, 3, 5, 3, 5]);
+        assert_eq!(ratings, vec![20, 24, 10, 4, 1, 4, 5, 8, 5]);
+        for stats in stats.iter() {
+            assert_eq!(stats.score, find_paths(&stats.position, &map, &boundaries));
+            assert_eq!(
+                stats.rating,
+                find_ratings(&stats.position, &map, &boundaries)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_map() {
+        let map = parse_map("0.2\n.45\n").expect("Failed to parse map");
+        assert_eq!(
+            map,
+            vec![vec![Some(0), None, Some(2)], vec![None, Some(4), Some(5)]]
+        );
+
+        let map = parse_map("0 10 .\n12 . 3\n").expect("Failed to parse map");
+        assert_eq!(
+            map,
+            vec![vec![Some(0), Some(10), None], vec![Some(12), None, Some(3)]]
+        );
+
+        assert!(parse_map("0x\n").is_err());
+    }
+
+    #[test]
+    fn test_general_puzzle_rules() {
+        let rules = TrailRules::puzzle();
+        let map =
+            parse_map("...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9\n").unwrap();
+        assert_eq!(general_score(&map, &(0, 3), &rules), 2);
+        assert_eq!(general_score(&map, &(3, 0), &rules), 0);
+
+        let map =
+            parse_map("..90..9\n...1.98\n...2..7\n6543456\n765.987\n876....\n987....\n").unwrap();
+        assert_eq!(general_score(&map, &(0, 3), &rules), 4);
+        assert_eq!(general_rating(&map, &(0, 3), &rules), Some(13));
+
+        let map =
+            parse_map(".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....\n").unwrap();
+        assert_eq!(general_rating(&map, &(0, 5), &rules), Some(3));
+    }
+
+    #[test]
+    fn test_general_custom_rules() {
+        let map = parse_map("0 1 2\n5 20 3\n. 10 4\n").unwrap();
+
+        let rules = TrailRules {
+            step: Box::new(|from, to| to > from && to - from <= 10),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([20]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        assert_eq!(general_score(&map, &(0, 0), &rules), 1);
+        assert_eq!(general_rating(&map, &(0, 0), &rules), Some(1));
+
+        let rules = TrailRules {
+            step: Box::new(|from, to| to == from + 1),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([2, 4]),
+            neighbourhood: Neighbourhood::Eight,
+        };
+        assert_eq!(general_score(&map, &(0, 0), &rules), 1);
+
+        let rules = TrailRules {
+            step: Box::new(|from, to| to <= from + 1),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([4]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        assert_eq!(general_score(&map, &(0, 0), &rules), 1);
+        assert_eq!(general_rating(&map, &(0, 0), &rules), None);
+    }
+
+    #[test]
+    fn test_trail_report() {
+        let rules = TrailRules::puzzle();
+        let map =
+            parse_map("...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9\n").unwrap();
+        assert_eq!(
+            reachable_summits(&map, &(0, 3), &rules),
+            vec![((6, 0), 9), ((6, 6), 9)]
+        );
+        let trails = enumerate_trails(&map, &(0, 3), &rules, 10);
+        assert_eq!(trails.len(), 2);
+        assert!(trails
+            .iter()
+            .all(|trail| trail.len() == 10 && trail[0] == (0, 3)));
+        assert_eq!(enumerate_trails(&map, &(0, 3), &rules, 1).len(), 1);
+
+        let map: TopographicMap = vec![
+            vec![8, 9, 0, 1, 0, 1, 2, 3],
+            vec![7, 8, 1, 2, 1, 8, 7, 4],
+            vec![8, 7, 4, 3, 0, 9, 6, 5],
+            vec![9, 6, 5, 4, 9, 8, 7, 4],
+            vec![4, 5, 6, 7, 8, 9, 0, 3],
+            vec![3, 2, 0, 1, 9, 0, 1, 2],
+            vec![0, 1, 3, 2, 9, 8, 0, 1],
+            vec![1, 0, 4, 5, 6, 7, 3, 2],
+        ];
+        let map = to_general_map(&map);
+        let report = trail_report(&map, &rules, 3);
+        assert_eq!(report.trailheads.len(), 9);
+        let top: Vec<Position> = report.top_by_score(2).iter().map(|s| s.position).collect();
+        assert_eq!(top, vec![(0, 4), (0, 2)]);
+        let top = report.top_by_rating(1);
+        assert_eq!((top[0].position, top[0].rating), ((0, 4), Some(24)));
+        assert!(top[0].truncated);
+        assert_eq!(top[0].trails.len(), 3);
+        assert_eq!(top[0].longest, Some(9));
+        assert_eq!(top[0].summits.len(), top[0].score);
+
+        let json = report.to_json(2);
+        assert!(json.starts_with("{\"trailheads\":[{\"position\":[0,2],\"score\":5,\"rating\":20,"));
+        assert!(json.ends_with("\"top_by_score\":[[0,4],[0,2]],\"top_by_rating\":[[0,4],[0,2]]}"));
+    }
+
+    #[test]
+    fn test_trail_report_custom_rules() {
+        let map = parse_map("0 1 2\n5 20 3\n. 10 4\n").unwrap();
+
+        let rules = TrailRules {
+            step: Box::new(|from, to| to > from && to - from <= 10),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([20]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        let report = trail_report(&map, &rules, 0);
+        let summary = &report.trailheads[0];
+        assert_eq!((summary.rating, summary.longest), (Some(1), Some(6)));
+        assert!(summary.trails.is_empty() && summary.truncated);
+
+        let rules = TrailRules {
+            step: Box::new(|from, to| to <= from + 1),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([4]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        let report = trail_report(&map, &rules, 5);
+        assert_eq!(report.trailheads.len(), 1);
+        let summary = &report.trailheads[0];
+        assert_eq!(
+            (summary.score, summary.rating, summary.longest),
+            (1, None, None)
+        );
+        assert_eq!(summary.shortest, Some(4));
+        assert_eq!(summary.trails.len(), 1);
+        assert!(summary.truncated);
+        assert!(report
+            .to_json(1)
+            .starts_with("{\"trailheads\":[{\"position\":[0,0],\"score\":1,\"rating\":null,"));
+    }
+
+    #[test]
+    fn test_enumerate_cyclic_trails() {
+        let map = parse_map("011\n.22\n").unwrap();
+        let rules = TrailRules {
+            step: Box::new(|from, to| to <= from + 1),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([2]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        let trails = enumerate_trails(&map, &(0, 0), &rules, 10);
+        assert_eq!(trails.len(), 2);
+        assert_eq!(
+            reachable_summits(&map, &(0, 0), &rules),
+            vec![((1, 1), 2), ((1, 2), 3)]
+        );
+        assert_eq!(general_rating(&map, &(0, 0), &rules), None);
+
+        let map = parse_map(&format!("0{}\n", "1".repeat(30))).unwrap();
+        let rules = TrailRules {
+            step: Box::new(|from, to| to <= from + 1),
+            starts: HashSet::from([0]),
+            ends: HashSet::from([2]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        assert!(enumerate_trails(&map, &(0, 0), &rules, 10).is_empty());
+        assert_eq!(general_rating(&map, &(0, 0), &rules), Some(0));
+    }
+
+    #[test]
+    fn test_enumerate_trails_budget() {
+        // The summit is only reachable from the start, so every trail into the open grid below
+        // dead-ends and the budget runs out before the direct step to the summit is tried.
+        let mut content = "21\n.".to_string() + &"1".repeat(7) + "\n";
+        for _ in 0..6 {
+            content += &"1".repeat(8);
+            content += "\n";
+        }
+        let map = parse_map(&content).unwrap();
+        let rules = TrailRules {
+            step: Box::new(|from, to| to <= from + 1),
+            starts: HashSet::from([1]),
+            ends: HashSet::from([2]),
+            neighbourhood: Neighbourhood::Four,
+        };
+        assert!(enumerate_trails(&map, &(0, 1), &rules, 1).is_empty());
+        assert_eq!(general_rating(&map, &(0, 1), &rules), None);
+    }
+
     #[test]
     fn test_day10_part1_and_part2() {
         let map = read_input("data/day10.txt").expect("Failed to read and parse the input file");
@@ -216,5 +957,11 @@ mod tests {
 
         assert_eq!(result_1, 593, "Day 10 - Part 1 failed");
         assert_eq!(result_2, 1192, "Day 09 - Part 2 failed");
+
+        let stats = general_trailhead_stats(&to_general_map(&map), &TrailRules::puzzle());
+        let result_1: usize = stats.iter().map(|stats| stats.score).sum();
+        let result_2: Option<usize> = stats.iter().map(|stats| stats.rating).sum();
+        assert_eq!(result_1, 593, "Day 10 - Part 1 general rules failed");
+        assert_eq!(result_2, Some(1192), "Day 10 - Part 2 general rules failed");
     }
 }
@@ -1,24 +1,76 @@
+use num_bigint::BigUint;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
+use std::hash::Hash;
 use std::path::Path;
+use std::str::FromStr;
 
 pub fn run() {
     let line = read_input("data/day11.txt").expect("Failed to read and parse the input file");
-    let result_1 = update_line(line.clone(), 25);
-    let result_2 = update_line_hashmap(&line, 75);
+    let mut counter = StoneCounter::<usize, BigUint>::default();
+    let result_1 = counter
+        .count_line(&line, 25)
+        .expect("Stone value overflowed");
+    let result_2 = counter
+        .count_line(&line, 75)
+        .expect("Stone value overflowed");
     println!("Day 11 - part 1: {}", result_1);
     println!("Day 11 - part 2: {}", result_2);
+    let result_500 = counter
+        .count_line(&line, 500)
+        .expect("Stone value overflowed");
+    println!("Day 11 - 500 blinks: {}", result_500);
+    let stats = counter.stats();
+    println!(
+        "Day 11 - cache: {} entries, {} hits, {} misses ({:.1}% hit rate)",
+        stats.entries,
+        stats.hits,
+        stats.misses,
+        stats.hit_rate() * 100.0
+    );
+}
+
+pub fn run_rules(path: Option<&str>) {
+    let line = read_input("data/day11.txt").expect("Failed to read and parse the input file");
+    let rules = match path {
+        Some(path) => fs::read_to_string(Path::new(path))
+            .map_err(|e| format!("Failed to read rules file '{}': {}", path, e))
+            .and_then(|text| text.parse::<RuleSet>()),
+        None => Ok(RuleSet::default()),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    print!("{}", rules);
+
+    let line: Vec<BigUint> = line.into_iter().map(BigUint::from).collect();
+    let mut counter = StoneCounter::<BigUint, BigUint>::with_rules(rules);
+    for n_blinks in [25, 75] {
+        match counter.count_line(&line, n_blinks) {
+            Ok(count) => println!("Day 11 - {} blinks: {}", n_blinks, count),
+            Err(e) => println!("Day 11 - {} blinks: {}", n_blinks, e),
+        }
+    }
 }
 
 pub fn read_input(path: &str) -> Result<Vec<usize>, String> {
+    read_input_as(path)
+}
+
+pub fn read_input_as<T: FromStr>(path: &str) -> Result<Vec<T>, String>
+where
+    T::Err: fmt::Display,
+{
     let content = fs::read_to_string(Path::new(path))
         .unwrap_or_else(|_| panic!("Failed to read input file: {}", path));
 
     content
         .split_whitespace()
         .map(|elem| {
-            elem.parse::<usize>()
+            elem.parse::<T>()
                 .map_err(|e| format!("Failed to parse '{}': {}", elem, e))
         })
         .collect()
@@ -43,14 +95,12 @@ fn update_stone(stone: &usize) -> Vec<usize> {
         vec![1]
     } else {
         let n = count_digits(stone);
-        if n % 2 == 0 {
+        if n.is_multiple_of(2) {
             match split_usize(stone, &n) {
                 Some((stone1, stone2)) => {
                     vec![stone1, stone2]
                 }
-                None => {
-                    Vec::new()
-                }
+                None => Vec::new(),
             }
         } else {
             vec![stone * 2024]
@@ -59,7 +109,7 @@ fn update_stone(stone: &usize) -> Vec<usize> {
 }
 
 fn blink(line: &[usize]) -> Vec<usize> {
-    line.iter().flat_map(|stone| update_stone(stone)).collect()
+    line.iter().flat_map(update_stone).collect()
 }
 
 pub fn update_line(line: Vec<usize>, n_blinks: usize) -> usize {
@@ -94,6 +144,505 @@ pub fn update_line_hashmap(line: &[usize], n_blinks: usize) -> usize {
     frequency.values().sum()
 }
 
+pub trait StoneNumber: Clone + Ord + Hash + FromStr + fmt::Display {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_small(value: u32) -> Option<Self>;
+    fn is_zero(&self) -> bool;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul_small(&self, factor: u32) -> Option<Self>;
+    fn count_digits(&self) -> usize;
+    fn split_digits(&self, low_digits: usize) -> (Self, Self);
+}
+
+macro_rules! impl_stone_number {
+    ($($t:ty),*) => {
+        $(
+            impl StoneNumber for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn from_small(value: u32) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *rhs)
+                }
+
+                fn checked_mul_small(&self, factor: u32) -> Option<Self> {
+                    <$t>::checked_mul(*self, <$t>::try_from(factor).ok()?)
+                }
+
+                fn count_digits(&self) -> usize {
+                    self.checked_ilog10().map_or(1, |log| log as usize + 1)
+                }
+
+                fn split_digits(&self, low_digits: usize) -> (Self, Self) {
+                    let power = <$t>::pow(10, low_digits as u32);
+                    (self / power, self % power)
+                }
+            }
+        )*
+    };
+}
+
+impl_stone_number!(u8, u16, u32, u64, u128, usize);
+
+impl StoneNumber for BigUint {
+    fn zero() -> Self {
+        BigUint::from(0u32)
+    }
+
+    fn one() -> Self {
+        BigUint::from(1u32)
+    }
+
+    fn from_small(value: u32) -> Option<Self> {
+        Some(BigUint::from(value))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.bits() == 0
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_mul_small(&self, factor: u32) -> Option<Self> {
+        Some(self * factor)
+    }
+
+    fn count_digits(&self) -> usize {
+        self.to_string().len()
+    }
+
+    fn split_digits(&self, low_digits: usize) -> (Self, Self) {
+        let power = BigUint::from(10u32).pow(low_digits as u32);
+        (self / &power, self % &power)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    ValueEquals(u32),
+    DigitsEven,
+    DigitsOdd,
+    DigitsEqual(usize),
+    Else,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Set(u32),
+    Split,
+    Multiply(u32),
+    Add(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub condition: Condition,
+    pub action: Action,
+}
+
+// Rules are tried in order and the first matching condition decides what the stone becomes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            rules: vec![
+                Rule {
+                    condition: Condition::ValueEquals(0),
+                    action: Action::Set(1),
+                },
+                Rule {
+                    condition: Condition::DigitsEven,
+                    action: Action::Split,
+                },
+                Rule {
+                    condition: Condition::Else,
+                    action: Action::Multiply(2024),
+                },
+            ],
+        }
+    }
+}
+
+impl Condition {
+    fn matches<V: StoneNumber>(&self, stone: &V) -> bool {
+        match self {
+            Condition::ValueEquals(value) => V::from_small(*value).as_ref() == Some(stone),
+            Condition::DigitsEven => stone.count_digits().is_multiple_of(2),
+            Condition::DigitsOdd => !stone.count_digits().is_multiple_of(2),
+            Condition::DigitsEqual(digits) => stone.count_digits() == *digits,
+            Condition::Else => true,
+        }
+    }
+}
+
+impl Action {
+    fn apply<V: StoneNumber>(&self, stone: &V) -> Result<Vec<V>, &'static str> {
+        let overflow = "overflows";
+        match self {
+            Action::Set(value) => Ok(vec![V::from_small(*value).ok_or(overflow)?]),
+            Action::Split => {
+                let digits = stone.count_digits();
+                if !digits.is_multiple_of(2) {
+                    return Err("has an odd number of digits");
+                }
+                let (left, right) = stone.split_digits(digits / 2);
+                Ok(vec![left, right])
+            }
+            Action::Multiply(factor) => {
+                Ok(vec![stone.checked_mul_small(*factor).ok_or(overflow)?])
+            }
+            Action::Add(term) => Ok(vec![V::from_small(*term)
+                .and_then(|term| stone.checked_add(&term))
+                .ok_or(overflow)?]),
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn apply<V: StoneNumber>(&self, stone: &V) -> Result<Vec<V>, String> {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule.condition.matches(stone))
+            .ok_or_else(|| format!("No rule matches stone {}", stone))?;
+        rule.action
+            .apply(stone)
+            .map_err(|reason| format!("Stone {} {} applying '{}'", stone, reason, rule))
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (condition, action) = line
+            .split_once("->")
+            .or_else(|| line.split_once('→'))
+            .ok_or_else(|| format!("Rule '{}' is missing '->'", line))?;
+        let number = |text: &str| {
+            text.trim()
+                .parse::<u32>()
+                .map_err(|e| format!("Invalid number '{}' in rule '{}': {}", text.trim(), line, e))
+        };
+
+        let words: Vec<&str> = condition.split_whitespace().collect();
+        let condition = match words.as_slice() {
+            ["else"] => Condition::Else,
+            ["digits", "even"] => Condition::DigitsEven,
+            ["digits", "odd"] => Condition::DigitsOdd,
+            ["digits", "==", digits] => Condition::DigitsEqual(number(digits)? as usize),
+            ["value", "==", value] => Condition::ValueEquals(number(value)?),
+            _ => return Err(format!("Unknown condition in rule '{}'", line)),
+        };
+
+        let action = action.trim();
+        let action = if action == "split" {
+            Action::Split
+        } else if let Some(factor) = action.strip_prefix('*').or(action.strip_prefix('×')) {
+            Action::Multiply(number(factor)?)
+        } else if let Some(term) = action.strip_prefix('+') {
+            Action::Add(number(term)?)
+        } else {
+            Action::Set(number(action)?)
+        };
+        Ok(Rule { condition, action })
+    }
+}
+
+// One rule per line (or separated by ';'); blank lines and '#' comments are ignored.
+impl FromStr for RuleSet {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rules = text
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or(""))
+            .flat_map(|line| line.split(';'))
+            .filter(|rule| !rule.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Rule>, String>>()?;
+        if rules.is_empty() {
+            return Err("Rule set is empty".to_string());
+        }
+        Ok(RuleSet { rules })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.condition {
+            Condition::ValueEquals(value) => write!(f, "value == {}", value)?,
+            Condition::DigitsEven => write!(f, "digits even")?,
+            Condition::DigitsOdd => write!(f, "digits odd")?,
+            Condition::DigitsEqual(digits) => write!(f, "digits == {}", digits)?,
+            Condition::Else => write!(f, "else")?,
+        }
+        match self.action {
+            Action::Set(value) => write!(f, " -> {}", value),
+            Action::Split => write!(f, " -> split"),
+            Action::Multiply(factor) => write!(f, " -> * {}", factor),
+            Action::Add(term) => write!(f, " -> + {}", term),
+        }
+    }
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn update_stone_checked<V: StoneNumber>(stone: &V) -> Result<Vec<V>, String> {
+    RuleSet::default().apply(stone)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+// Memoises the number of stones a single stone turns into after a given number of blinks.
+// The cache is kept between calls, so asking for more blinks reuses every shorter horizon.
+pub struct StoneCounter<V = usize, C = usize> {
+    rules: RuleSet,
+    cache: HashMap<(V, usize), C>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<V, C> Default for StoneCounter<V, C> {
+    fn default() -> Self {
+        Self::with_rules(RuleSet::default())
+    }
+}
+
+impl<V, C> StoneCounter<V, C> {
+    pub fn with_rules(rules: RuleSet) -> Self {
+        StoneCounter {
+            rules,
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl StoneCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<V: StoneNumber, C: StoneNumber> StoneCounter<V, C> {
+    pub fn count(&mut self, stone: &V, n_blinks: usize) -> Result<C, String> {
+        if n_blinks == 0 {
+            return Ok(C::one());
+        }
+        if let Some(count) = self.cache.get(&(stone.clone(), n_blinks)) {
+            self.hits += 1;
+            return Ok(count.clone());
+        }
+        self.misses += 1;
+        let mut count = C::zero();
+        for next in self.rules.apply(stone)? {
+            let next_count = self.count(&next, n_blinks - 1)?;
+            count = count.checked_add(&next_count).ok_or_else(|| {
+                format!(
+                    "Stone count overflows after {} blinks of {}",
+                    n_blinks, stone
+                )
+            })?;
+        }
+        self.cache.insert((stone.clone(), n_blinks), count.clone());
+        Ok(count)
+    }
+
+    pub fn count_line(&mut self, line: &[V], n_blinks: usize) -> Result<C, String> {
+        let mut total = C::zero();
+        for stone in line {
+            let count = self.count(stone, n_blinks)?;
+            total = total
+                .checked_add(&count)
+                .ok_or_else(|| format!("Stone count overflows after {} blinks", n_blinks))?;
+        }
+        Ok(total)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.cache.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+// Stone values with their multiplicities; the order of stones never matters for counting.
+#[derive(Debug, Clone)]
+pub struct StoneDistribution<V = usize, C = usize> {
+    counts: HashMap<V, C>,
+}
+
+impl<V: StoneNumber, C: StoneNumber> StoneDistribution<V, C> {
+    pub fn from_line(line: &[V]) -> Result<Self, String> {
+        let mut distribution = StoneDistribution {
+            counts: HashMap::new(),
+        };
+        for stone in line {
+            distribution.add(stone.clone(), &C::one())?;
+        }
+        Ok(distribution)
+    }
+
+    fn add(&mut self, stone: V, count: &C) -> Result<(), String> {
+        let entry = self.counts.entry(stone).or_insert_with(C::zero);
+        *entry = entry
+            .checked_add(count)
+            .ok_or_else(|| "Stone count overflows".to_string())?;
+        Ok(())
+    }
+
+    pub fn blink(&self, rules: &RuleSet) -> Result<Self, String> {
+        let mut next = StoneDistribution {
+            counts: HashMap::new(),
+        };
+        for (stone, count) in &self.counts {
+            for child in rules.apply(stone)? {
+                next.add(child, count)?;
+            }
+        }
+        Ok(next)
+    }
+
+    pub fn total(&self) -> Result<C, String> {
+        self.counts.values().try_fold(C::zero(), |total, count| {
+            total
+                .checked_add(count)
+                .ok_or_else(|| "Stone count overflows".to_string())
+        })
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn count_of(&self, stone: &V) -> C {
+        self.counts.get(stone).cloned().unwrap_or_else(C::zero)
+    }
+
+    pub fn histogram(&self) -> Vec<(V, C)> {
+        let mut histogram: Vec<(V, C)> = self
+            .counts
+            .iter()
+            .map(|(stone, count)| (stone.clone(), count.clone()))
+            .collect();
+        histogram.sort();
+        histogram
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrowthRow<C = usize> {
+    pub blink: usize,
+    pub total: C,
+    pub distinct: usize,
+    pub seen: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Growth<C = usize> {
+    pub rows: Vec<GrowthRow<C>>,
+    pub closed_at: Option<usize>,
+}
+
+// Once a blink produces no value that was never seen before, every later value is a child of
+// an already expanded value, so the set of reachable values is closed from that blink on.
+pub fn growth<V: StoneNumber, C: StoneNumber>(
+    line: &[V],
+    rules: &RuleSet,
+    n_blinks: usize,
+) -> Result<Growth<C>, String> {
+    let mut distribution = StoneDistribution::<V, C>::from_line(line)?;
+    let mut seen: HashSet<V> = distribution.counts.keys().cloned().collect();
+    let mut rows = vec![GrowthRow {
+        blink: 0,
+        total: distribution.total()?,
+        distinct: distribution.distinct(),
+        seen: seen.len(),
+    }];
+    let mut closed_at = None;
+    for blink in 1..=n_blinks {
+        distribution = distribution.blink(rules)?;
+        let before = seen.len();
+        seen.extend(distribution.counts.keys().cloned());
+        if closed_at.is_none() && seen.len() == before {
+            closed_at = Some(blink);
+        }
+        rows.push(GrowthRow {
+            blink,
+            total: distribution.total()?,
+            distinct: distribution.distinct(),
+            seen: seen.len(),
+        });
+    }
+    Ok(Growth { rows, closed_at })
+}
+
+pub fn run_growth() {
+    let line = read_input("data/day11.txt").expect("Failed to read and parse the input file");
+    let growth: Growth = growth(&line, &RuleSet::default(), 75).expect("Stone count overflowed");
+    println!(
+        "{:>6} {:>18} {:>9} {:>6}",
+        "blink", "stones", "distinct", "seen"
+    );
+    for row in &growth.rows {
+        println!(
+            "{:>6} {:>18} {:>9} {:>6}",
+            row.blink, row.total, row.distinct, row.seen
+        );
+    }
+    match growth.closed_at {
+        Some(blink) => println!("Distinct values stop growing after blink {}", blink),
+        None => println!("Distinct values are still growing after 75 blinks"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,6 +673,190 @@ mod tests {
         assert_eq!(expected, result, "Test update line failed");
     }
 
+    #[test]
+    fn test_stone_counter() {
+        let mut counter = StoneCounter::new();
+        assert_eq!(counter.count_line(&[125, 17], 6), Ok(22));
+        assert_eq!(counter.count_line(&[125, 17], 25), Ok(55312));
+        assert_eq!(counter.count(&0, 0), Ok(1));
+        for n_blinks in 0..10 {
+            assert_eq!(
+                counter.count(&7, n_blinks),
+                Ok(update_line(vec![7], n_blinks)),
+                "Test stone counter failed for {} blinks",
+                n_blinks
+            );
+        }
+    }
+
+    #[test]
+    fn test_stone_counter_reuses_cache() {
+        let mut counter = StoneCounter::new();
+        counter.count_line(&[125, 17], 25).unwrap();
+        let after_25 = counter.stats();
+        assert_eq!(after_25.misses, after_25.entries);
+
+        counter.count_line(&[125, 17], 25).unwrap();
+        let again = counter.stats();
+        assert_eq!(again.misses, after_25.misses, "Repeat should only hit");
+        assert_eq!(again.hits, after_25.hits + 2);
+
+        counter.count_line(&[125, 17], 26).unwrap();
+        let after_26 = counter.stats();
+        assert!(after_26.misses - after_25.misses < after_25.misses);
+        assert!(after_26.hit_rate() > 0.0);
+
+        assert!(counter.count_line(&[125, 17], 500).is_err());
+    }
+
+    #[test]
+    fn test_update_stone_checked() {
+        for stone in [0usize, 1, 10, 99, 999, 2024, 123456] {
+            assert_eq!(update_stone_checked(&stone), Ok(update_stone(&stone)));
+            assert_eq!(
+                update_stone_checked(&BigUint::from(stone)),
+                Ok(update_stone(&stone)
+                    .into_iter()
+                    .map(BigUint::from)
+                    .collect())
+            );
+        }
+        assert_eq!(update_stone_checked(&1000u16), Ok(vec![10, 0]));
+        assert!(update_stone_checked(&1u8).is_err());
+        assert!(update_stone_checked(&(u64::MAX / 1000)).is_err());
+    }
+
+    #[test]
+    fn test_stone_counter_numeric_types() {
+        let mut small = StoneCounter::<u32, u16>::default();
+        assert_eq!(small.count_line(&[125, 17], 25), Ok(55312));
+        assert!(small.count_line(&[125, 17], 30).is_err());
+        assert!(StoneCounter::<u8, usize>::default().count(&1, 1).is_err());
+
+        let mut wide = StoneCounter::<u64, u128>::default();
+        let mut big = StoneCounter::<BigUint, BigUint>::default();
+        let line: Vec<BigUint> = vec![BigUint::from(125u32), BigUint::from(17u32)];
+        let expected = wide.count_line(&[125, 17], 150).unwrap();
+        assert_eq!(big.count_line(&line, 150), Ok(BigUint::from(expected)));
+        assert!(wide.count_line(&[125, 17], 250).is_err());
+
+        let count = big.count_line(&line, 500).unwrap();
+        assert_eq!(count.to_string().len(), 91);
+    }
+
+    #[test]
+    fn test_parse_rule_set() {
+        let rules: RuleSet = "value == 0 -> 1\ndigits even -> split\nelse -> * 2024\n"
+            .parse()
+            .expect("Failed to parse rules");
+        assert_eq!(rules, RuleSet::default());
+        assert_eq!(rules.to_string().parse::<RuleSet>(), Ok(rules.clone()));
+
+        let rules: RuleSet =
+            "# variant\nvalue == 0 → 7; digits == 3 -> +1\ndigits odd -> ×3\nelse -> split"
+                .parse()
+                .expect("Failed to parse rules");
+        assert_eq!(
+            rules.rules,
+            vec![
+                Rule {
+                    condition: Condition::ValueEquals(0),
+                    action: Action::Set(7),
+                },
+                Rule {
+                    condition: Condition::DigitsEqual(3),
+                    action: Action::Add(1),
+                },
+                Rule {
+                    condition: Condition::DigitsOdd,
+                    action: Action::Multiply(3),
+                },
+                Rule {
+                    condition: Condition::Else,
+                    action: Action::Split,
+                },
+            ]
+        );
+
+        assert!("value = 0 -> 1".parse::<RuleSet>().is_err());
+        assert!("else 1".parse::<RuleSet>().is_err());
+        assert!("else -> double".parse::<RuleSet>().is_err());
+        assert!("# nothing".parse::<RuleSet>().is_err());
+    }
+
+    #[test]
+    fn test_custom_rule_set() {
+        let rules: RuleSet = "value == 0 -> 1\ndigits even -> split\nelse -> * 3"
+            .parse()
+            .unwrap();
+        assert_eq!(rules.apply(&0usize), Ok(vec![1]));
+        assert_eq!(rules.apply(&1234usize), Ok(vec![12, 34]));
+        assert_eq!(rules.apply(&5usize), Ok(vec![15]));
+
+        let mut counter = StoneCounter::<usize, usize>::with_rules(rules);
+        assert_eq!(counter.count(&0, 5), Ok(2));
+
+        let rules: RuleSet = "value == 0 -> 1".parse().unwrap();
+        assert!(rules.apply(&5usize).is_err());
+        let rules: RuleSet = "else -> 300".parse().unwrap();
+        assert!(rules.apply(&5u8).is_err());
+
+        let rules: RuleSet = "else -> split".parse().unwrap();
+        assert_eq!(rules.apply(&10usize), Ok(vec![1, 0]));
+        assert_eq!(rules.apply(&123456usize), Ok(vec![123, 456]));
+        for stone in [0usize, 5, 123] {
+            assert!(
+                rules.apply(&stone).is_err(),
+                "Splitting {} should fail",
+                stone
+            );
+        }
+        assert!(StoneCounter::<usize, usize>::with_rules(rules)
+            .count(&1234, 3)
+            .is_err());
+    }
+
+    #[test]
+    fn test_stone_distribution() {
+        let rules = RuleSet::default();
+        let mut distribution = StoneDistribution::<usize, usize>::from_line(&[125, 17]).unwrap();
+        for _ in 0..6 {
+            distribution = distribution.blink(&rules).unwrap();
+        }
+        assert_eq!(distribution.total(), Ok(22));
+        assert_eq!(distribution.distinct(), 15);
+        assert_eq!(distribution.count_of(&2), 4);
+        assert_eq!(distribution.count_of(&5), 0);
+        let histogram = distribution.histogram();
+        assert_eq!(&histogram[..3], &[(0, 2), (2, 4), (3, 1)]);
+        assert_eq!(histogram.iter().map(|(_, count)| count).sum::<usize>(), 22);
+
+        let line = [0usize, 0, 1];
+        let distribution = StoneDistribution::<usize, usize>::from_line(&line).unwrap();
+        assert_eq!(distribution.histogram(), vec![(0, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn test_growth() {
+        let result: Growth = growth(&[125usize, 17], &RuleSet::default(), 25).unwrap();
+        assert_eq!(result.rows.len(), 26);
+        assert_eq!(result.rows[6].total, 22);
+        assert_eq!(result.rows[25].total, 55312);
+        assert!(result
+            .rows
+            .windows(2)
+            .all(|rows| rows[0].seen <= rows[1].seen));
+
+        let result: Growth = growth(&[0usize], &RuleSet::default(), 100).unwrap();
+        let closed_at = result.closed_at.expect("Values should close");
+        let closed = result.rows[closed_at].seen;
+        assert!(result.rows[closed_at..]
+            .iter()
+            .all(|row| row.seen == closed));
+        assert_eq!(result.rows[closed_at - 1].seen, closed);
+        assert!(result.rows[closed_at - 2].seen < closed);
+    }
+
     #[test]
     fn test_day11_part1_and_part2() {
         let line = read_input("data/day11.txt").expect("Failed to read and parse the input file");
@@ -131,5 +864,9 @@ mod tests {
         let result_2 = update_line_hashmap(&line, 75);
         assert_eq!(result_1, 186424, "Day 11 - Part 1 failed");
         assert_eq!(result_2, 219838428124832, "Day 11 - Part 2 failed");
+
+        let mut counter = StoneCounter::new();
+        assert_eq!(counter.count_line(&line, 25), Ok(186424));
+        assert_eq!(counter.count_line(&line, 75), Ok(219838428124832));
     }
 }
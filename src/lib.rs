@@ -1 +1,3 @@
+pub mod combinatorics;
 pub mod days;
+pub mod random;
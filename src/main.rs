@@ -5,7 +5,7 @@ fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
-        eprintln!("Usage: {} <day>", args[0]);
+        eprintln!("Usage: {} <day> [mode] [mode args]", args[0]);
         std::process::exit(1);
     }
 
@@ -14,21 +14,68 @@ fn main() {
         std::process::exit(1);
     });
 
+    let mode = args.get(2).map(String::as_str);
+
     match day {
-        1 => days::day01::run(),
-        2 => days::day02::run(),
-        3 => days::day03::run(),
-        4 => days::day04::run(),
-        5 => days::day05::run(),
-        6 => days::day06::run(),
-        7 => days::day07::run(),
-        8 => days::day08::run(),
-        9 => days::day09::run(),
-        10 => days::day10::run(),
-        11 => days::day11::run(),
+        1 => match mode {
+            None => days::day01::run(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        2 => match mode {
+            None => days::day02::run(),
+            Some("diagnostics") => days::day02::run_diagnostics(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        3 => match mode {
+            None => days::day03::run(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        4 => match mode {
+            None => days::day04::run(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        5 => match mode {
+            None => days::day05::run(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        6 => match mode {
+            None => days::day06::run(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        7 => match mode {
+            None => days::day07::run(),
+            Some("witness") => days::day07::run_witnesses(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        8 => match mode {
+            None => days::day08::run(),
+            Some("report") => days::day08::run_report(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        9 => match mode {
+            None => days::day09::run(),
+            Some("strategies") => days::day09::run_strategies(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        10 => match mode {
+            None => days::day10::run(),
+            Some("json") => days::day10::run_json(),
+            Some(mode) => unknown_mode(day, mode),
+        },
+        11 => match mode {
+            None => days::day11::run(),
+            Some("rules") => days::day11::run_rules(args.get(3).map(String::as_str)),
+            Some("growth") => days::day11::run_growth(),
+            Some(mode) => unknown_mode(day, mode),
+        },
         _ => {
             eprintln!("Day {} is not implemented", day);
             std::process::exit(1);
         }
     }
 }
+
+fn unknown_mode(day: u32, mode: &str) {
+    eprintln!("Day {} has no mode '{}'", day, mode);
+    std::process::exit(1);
+}
@@ -0,0 +1,38 @@
+// Small deterministic generator for randomised tests and synthetic benchmark inputs.
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcg() {
+        let mut rng = Lcg::new(1);
+        let values: Vec<u64> = (0..1000).map(|_| rng.next_below(10)).collect();
+        assert!(values.iter().all(|&value| value < 10), "Value out of range");
+        assert!(
+            (0..10).all(|digit| values.contains(&digit)),
+            "Every value below the bound should appear"
+        );
+
+        let mut other = Lcg::new(1);
+        let replayed: Vec<u64> = (0..1000).map(|_| other.next_below(10)).collect();
+        assert_eq!(values, replayed, "Same seed should give the same sequence");
+    }
+}